@@ -7,10 +7,480 @@
  * SPDX-License-Identifier: EUPL-1.2
  */
 
-const O_VERTEX_WITH_BORDER: [(i8, i8); 7] = [(-1, 0), (0, 0), (-1, -1), (0, 0), (0, -1), (0, 0), (0, 0)]; // Bottom left coordinates with a border
-const H_VERTEX_WITH_BORDER: [(i8, i8); 7] = [(0, 0), (0, 0), (-1, 0), (0, 0), (-1, -1), (0, 0), (0, -1)]; // Bottom right coordinates with a border
-const O_VALUE_FOR_SIGNED:   [i8; 7]       = [1, 0, 2, 0, 4, 0, 8];     // Value to add into an array of contours (using signed integers)
-const H_VALUE_FOR_SIGNED:   [i8; 7]       = [-4, 0, -8, 0, -1, 0, -2]; // (idem)
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use super::contour::number;
+
+/// The order in which the cells of a 2D array of bits are scanned to find the contours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanOrder {
+    /// Scan the rows from top to bottom, each row from left to right (default)
+    RowMajor,
+    /// Scan square tiles of the given size in Morton (Z-order) order, for a better cache locality on very large arrays
+    ///
+    /// The contours are the same as with [`ScanOrder::RowMajor`] and they are sorted back into the same order.
+    Morton(usize),
+}
+
+/// The connectivity of the foreground bits, see [`bits_to_paths_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// The bits touching only by a corner are in different contours (the default of the other functions)
+    Four,
+    /// The bits touching only by a corner are in the same contour (and the background bits are 4-connected)
+    Eight,
+}
+
+/// The order of the contours in the output of [`bits_to_paths_with_options`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputOrder {
+    /// The order in which the contours are found by the scan (the default)
+    Scan,
+    /// A fully deterministic order, e.g. for golden-file tests: the outlines are sorted by top, left, then area,
+    /// each outline is followed by its holes (sorted the same way) and each hole by the outlines inside it.
+    /// The first vertex of every contour is its top left vertex.
+    Canonical,
+}
+
+/// The style of the corners of the paths, see [`TraceOptions::corner_style`].
+#[derive(Clone, Copy, Debug)]
+pub enum CornerStyle {
+    /// Right-angle corners (the default)
+    Sharp,
+    /// The corners are cut by a straight line (SVG Path **L** command) at the given distance in pixels from the corner
+    Chamfer(f64),
+    /// The corners are replaced by quarter-circle arcs (SVG Path **A** command) of the given radius in pixels
+    Round(f64),
+}
+
+// The lengths are compared by their bits, so a style is always equal to itself
+impl PartialEq for CornerStyle {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CornerStyle::Sharp, CornerStyle::Sharp) => true,
+            (CornerStyle::Chamfer(a), CornerStyle::Chamfer(b)) | (CornerStyle::Round(a), CornerStyle::Round(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CornerStyle {}
+
+/// The winding convention of the glyph outlines, see [`bits_to_glyph_paths`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontWinding {
+    /// TrueType (`glyf` table): the outer contours are clockwise and the counters (holes) are counterclockwise
+    TrueType,
+    /// PostScript (CFF, Type 1): the outer contours are counterclockwise and the counters (holes) are clockwise
+    PostScript,
+}
+
+/// The orientation of the tracer in a step of a [`ContourWalk`] (the y axis points down).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Towards the row above
+    North,
+    /// Towards the next column
+    East,
+    /// Towards the row below
+    South,
+    /// Towards the previous column
+    West,
+}
+
+/// The kinds of contours given by [`bits_to_paths_filtered`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContourFilter {
+    /// The outlines and the holes
+    All,
+    /// The outlines only (the outlines of the islands in the holes included), e.g. for the silhouettes of the shapes
+    OutlinesOnly,
+    /// The holes only
+    HolesOnly,
+}
+
+/// The fill rule used to render the concatenated paths, see [`bits_to_paths_for_fill_rule`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is filled if a ray from it crosses the contours an odd number of times, whatever their direction
+    EvenOdd,
+    /// A point is filled if the winding number of the contours around it is not zero
+    NonZero,
+}
+
+impl FillRule {
+    /// The value of the SVG `fill-rule` attribute: `evenodd` or `nonzero`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FillRule::EvenOdd => "evenodd",
+            FillRule::NonZero => "nonzero",
+        }
+    }
+}
+
+/// The bits of the adjacent tiles along the borders of a tile, see [`bits_to_paths_with_borders`].
+///
+/// The `top` and `bottom` strips are the last row of the tile above and the first row of the tile below, they include
+/// the corner bits so they have `cols + 2` bits. The `left` and `right` strips are the last column of the tile on the left
+/// and the first column of the tile on the right, they have `rows` bits. The missing bits (e.g. empty strips) are background.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::TileBorders;
+/// let borders = TileBorders { left: vec![ 1,1 ], ..Default::default() };
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TileBorders {
+    /// The bits above the tile, from the top left corner to the top right corner
+    pub top: Vec<i8>,
+    /// The bits below the tile, from the bottom left corner to the bottom right corner
+    pub bottom: Vec<i8>,
+    /// The bits on the left of the tile, from top to bottom
+    pub left: Vec<i8>,
+    /// The bits on the right of the tile, from top to bottom
+    pub right: Vec<i8>,
+}
+
+/// What [`bits_to_paths_within_bounds`] does with the vertices outside the bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundsPolicy {
+    /// Clamp the vertices into the bounds, which clips the parts of the contours outside the bounds (the contours are rectilinear),
+    /// and drop the contours which are entirely outside
+    Clamp,
+    /// Return an [`OutOfBounds`] error for the first vertex outside the bounds
+    Error,
+}
+
+/// The error of [`bits_to_paths_within_bounds`] with [`BoundsPolicy::Error`]: a vertex of a contour is outside the bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    /// The id of the contour (as in [`bits_to_contours_with_ids`])
+    pub id: usize,
+    /// The vertex outside the bounds
+    pub point: (i32, i32),
+}
+
+impl std::fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the vertex ({}, {}) of the contour {} is out of bounds", self.point.0, self.point.1, self.id)
+    }
+}
+
+impl std::error::Error for OutOfBounds {}
+
+/// The error of [`diff_paths`] and [`masked_trace`]: the two 2D arrays do not have the same dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    /// The number of rows and the number of columns (of the first row) of the first array
+    pub a: (usize, usize),
+    /// The number of rows and the number of columns (of the first row) of the second array (or of the region of interest)
+    pub b: (usize, usize),
+}
+
+impl std::fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the arrays of bits have different dimensions ({}x{} and {}x{})", self.a.0, self.a.1, self.b.0, self.b.1)
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
+/// The error of [`try_bits_to_paths`]: the 2D array of bits is malformed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceError {
+    /// The array has no row
+    Empty,
+    /// A row does not have the same number of columns as the first row
+    RaggedRows {
+        /// The index of the row
+        row: usize,
+        /// The number of columns of the first row
+        expected: usize,
+        /// The number of columns of the row
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TraceError::Empty => write!(f, "the array of bits has no row"),
+            TraceError::RaggedRows { row, expected, found } => write!(f, "the row {} of the array of bits has {} columns instead of {}", row, found, expected),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+/// The contours (vecs of pixels) and their hierarchy (`[next, previous, first_child, parent]`), see [`bits_to_opencv_contours`].
+pub type OpenCvContours = (Vec<Vec<(i32, i32)>>, Vec<[i32; 4]>);
+
+/// The options of [`bits_to_paths_with_options`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{ScanOrder, TraceOptions};
+/// let options = TraceOptions::new().close_paths(true).scan_order(ScanOrder::Morton(64));
+/// ```
+/// - The options are added without breaking the call sites, and the other functions use the default options:
+///
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths, bits_to_paths_with_options, Connectivity, TraceOptions};
+/// let bits = vec![vec![ 1,0 ],
+///                 vec![ 0,1 ]];
+///
+/// let options = TraceOptions::new().close_paths(true).connectivity(Connectivity::Eight);
+/// # assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H1V1H2V2H1V1H0Z");
+/// # assert_eq!(bits_to_paths_with_options(&bits, &TraceOptions::new()), bits_to_paths(bits.to_vec(), false));
+/// println!("{}", bits_to_paths_with_options(&bits, &options));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceOptions {
+    closepaths: bool,
+    scan_order: ScanOrder,
+    relative: bool,
+    output_order: OutputOrder,
+    normalize_start: bool,
+    row_range: Option<Range<u32>>,
+    max_depth: Option<u32>,
+    border_is_foreground: bool,
+    quantize: u32,
+    y_up: Option<i32>,
+    flip_y: bool,
+    frame_boundary: super::contour::FrameBoundary,
+    connectivity: Connectivity,
+    corner_style: CornerStyle,
+    min_contour_area: u64,
+    precision: u8,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        TraceOptions { closepaths: false, scan_order: ScanOrder::RowMajor, relative: false, output_order: OutputOrder::Scan, normalize_start: false, row_range: None, max_depth: None, border_is_foreground: false, quantize: 1, y_up: None, flip_y: false, frame_boundary: super::contour::FrameBoundary::Closed, connectivity: Connectivity::Four, corner_style: CornerStyle::Sharp, min_contour_area: 0, precision: 6 }
+    }
+}
+
+impl TraceOptions {
+    /// Create the default options: the paths are not closed, the scan order is [`ScanOrder::RowMajor`], the coordinates are absolute
+    /// and the output order is [`OutputOrder::Scan`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Close or not the paths (with the SVG Path **Z** command).
+    pub fn close_paths(mut self, closepaths: bool) -> Self {
+        self.closepaths = closepaths;
+        self
+    }
+
+    /// Set the order in which the cells are scanned.
+    pub fn scan_order(mut self, scan_order: ScanOrder) -> Self {
+        self.scan_order = scan_order;
+        self
+    }
+
+    /// Use or not the relative SVG Path commands (**m**, **h**, **v** and **z**).
+    ///
+    /// Each coordinate is the integer delta from the current point, so the string is much smaller for large arrays of bits.
+    /// The first **m** of a path is relative to the current point of the previous path, and the paths draw exactly the same shapes:
+    /// after a **z** the current point is the first vertex of the closed path, and the leading **m** of the string is relative
+    /// to the origin, so it is the same as an absolute **M** (as specified by SVG for a path starting with **m**).
+    pub fn relative_coordinates(mut self, relative: bool) -> Self {
+        self.relative = relative;
+        self
+    }
+
+    /// Set the order of the contours in the output.
+    pub fn output_order(mut self, output_order: OutputOrder) -> Self {
+        self.output_order = output_order;
+        self
+    }
+
+    /// Start or not each path at the lexicographically smallest `(x, y)` vertex of its contour, see [`Contour::normalize_start`](super::contour::Contour::normalize_start).
+    ///
+    /// By default, each path starts at the top left vertex of its contour (the leftmost of its topmost vertices), where the tracer
+    /// enters the contour, so no rotation is needed for a deterministic start. This option selects the leftmost vertex first instead.
+    pub fn normalize_start(mut self, normalize_start: bool) -> Self {
+        self.normalize_start = normalize_start;
+        self
+    }
+
+    /// Trace only the contours which intersect the rows `y0..y1`, e.g. to render the bands of a progressively decoded image.
+    ///
+    /// A contour intersects the rows if at least one of its bits is in the rows, and it is then emitted whole (not clipped).
+    /// The scan stops after the last row of the range. By default, all the rows are traced.
+    pub fn row_range(mut self, row_range: Range<u32>) -> Self {
+        self.row_range = Some(row_range);
+        self
+    }
+
+    /// Skip the contours nested deeper than `max_depth` levels: the outlines which are not in a hole are at the level **1**,
+    /// their holes at the level **2**, the outlines in these holes at the level **3**, and so on. By default, the depth is unlimited.
+    pub fn max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Skip the contours which enclose less than `min_contour_area` cells (their shoelace area, before [`TraceOptions::quantize`]),
+    /// e.g. **2** to drop the isolated single cells of a thresholded mask. By default (**0** or **1**), every contour is kept.
+    ///
+    /// The outlines and the holes are filtered alike, so the small holes are filled, and the contours inside a skipped outline are kept.
+    pub fn min_contour_area(mut self, min_contour_area: u64) -> Self {
+        self.min_contour_area = min_contour_area;
+        self
+    }
+
+    /// Consider the outside of the array of bits as foreground (or as background, the default).
+    ///
+    /// The shapes are then assumed to extend infinitely beyond the sides: the background regions which touch the sides
+    /// are traced as holes along the sides, and the foreground bits which touch the sides are not outlined there.
+    /// The outlines of the shapes which do not touch the sides are unchanged, and their levels for [`TraceOptions::max_depth`]
+    /// start at **2** since they are in a hole.
+    pub fn border_is_foreground(mut self, border_is_foreground: bool) -> Self {
+        self.border_is_foreground = border_is_foreground;
+        self
+    }
+
+    /// Snap the vertices of the contours to the nearest multiple of `quantize` (**1**, the default, keeps them unchanged),
+    /// then merge the resulting collinear edges, e.g. for chunky low-resolution previews.
+    ///
+    /// Unlike downsampling the array of bits before tracing, which can erase the shapes smaller than a cell of the coarser grid,
+    /// every contour is kept: a contour which collapses once snapped is replaced by the cells of the coarser grid that cover it.
+    /// The first vertex of each contour is still its top left vertex.
+    pub fn quantize(mut self, quantize: u32) -> Self {
+        self.quantize = quantize.max(1);
+        self
+    }
+
+    /// Measure the y coordinates from the bottom of a document of the given `height`: each `y` is emitted as `height - y`,
+    /// e.g. for a bottom-origin SVG viewport. By default, the y axis points down from the top of the array of bits.
+    ///
+    /// The `height` of the document can differ from the number of rows (e.g. a padded canvas). The vertices after the first one
+    /// are emitted in the reverse order, so in the y-up space the outlines are counterclockwise and the holes clockwise.
+    pub fn y_up(mut self, height: u32) -> Self {
+        self.y_up = Some(height.min(i32::MAX as u32) as i32);
+        self
+    }
+
+    /// Same as [`TraceOptions::y_up`] with the height of the array of bits (its number of rows, rounded like the coordinates
+    /// by [`TraceOptions::quantize`]), e.g. for a CAD or GIS system with a bottom left origin: each `y` is emitted as `height - y`.
+    ///
+    /// The vertices after the first one are emitted in the reverse order (as by [`bits_to_glyph_paths`]), so in the y-up space
+    /// the outlines are counterclockwise and the holes clockwise: their signed areas are still positive and negative.
+    /// An explicit height given to [`TraceOptions::y_up`] takes precedence.
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Set the boundary of the regions which touch the sides of the array of bits, along the sides (for [`bits_to_paths_with_options`]).
+    ///
+    /// With [`FrameBoundary::Open`](super::contour::FrameBoundary::Open), the sides are the ones of the array of bits
+    /// (after [`TraceOptions::quantize`] and [`TraceOptions::y_up`]). By default, the contours are always closed.
+    pub fn frame_boundary(mut self, frame_boundary: super::contour::FrameBoundary) -> Self {
+        self.frame_boundary = frame_boundary;
+        self
+    }
+
+    /// Set the connectivity of the foreground bits, see [`bits_to_paths_with`]. By default, it is [`Connectivity::Four`].
+    ///
+    /// With [`Connectivity::Eight`], the contours are always found in the row-major order, whatever the [`TraceOptions::scan_order`].
+    pub fn connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Set the style of the corners (for [`bits_to_paths_with_options`]): sharp (the default), chamfered or rounded.
+    ///
+    /// The length of each chamfer or radius is clamped to half of the two edges of its corner, so the corners of a feature
+    /// of 1 pixel never overlap (a rounded pixel is a circle). A styled path starts after the corner of the first vertex
+    /// and ends with it, so the shape is complete whatever the **closepaths option**. The styled paths always use
+    /// absolute commands: with [`TraceOptions::relative_coordinates`] or an open [`TraceOptions::frame_boundary`]
+    /// the corners stay sharp. A length of **0** or less gives sharp corners.
+    pub fn corner_style(mut self, corner_style: CornerStyle) -> Self {
+        self.corner_style = corner_style;
+        self
+    }
+
+    /// Set the number of decimals of the fractional coordinates of the styled corners (see [`TraceOptions::corner_style`]),
+    /// **6** by default. The coordinates are rounded and given without their trailing zeros, e.g. `3` instead of `3.00`.
+    pub fn precision(mut self, precision: u8) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    // The height of the y-up space, if any
+    fn y_up_height(&self, rows: usize) -> Option<i32> {
+        let q = self.quantize as i32;
+        self.y_up.or(if self.flip_y { Some((rows as i32 + q / 2).div_euclid(q) * q) } else { None })
+    }
+
+    // The top left and bottom right corners of the array of bits in the output coordinates
+    fn output_frame(&self, rows: usize, cols: usize) -> ((i32, i32), (i32, i32)) {
+        let q = self.quantize as i32;
+        let (x1, y1) = ((cols as i32 + q / 2).div_euclid(q) * q, (rows as i32 + q / 2).div_euclid(q) * q);
+        match self.y_up_height(rows) {
+            Some(height) => ((0, height), (x1, height - y1)),
+            None => ((0, 0), (x1, y1)),
+        }
+    }
+}
+
+/// The options of [`bits_to_svg_document`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::SvgDocOptions;
+/// let options = SvgDocOptions::new().fill("#336").stroke("black").size("64", "64").background("white");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgDocOptions {
+    fill: String,
+    stroke: Option<String>,
+    fill_rule: String,
+    size: Option<(String, String)>,
+    background: Option<String>,
+}
+
+impl Default for SvgDocOptions {
+    fn default() -> Self {
+        SvgDocOptions { fill: "black".to_string(), stroke: None, fill_rule: "evenodd".to_string(), size: None, background: None }
+    }
+}
+
+impl SvgDocOptions {
+    /// Create the default options: a black fill without stroke, the `evenodd` fill rule, no size and no background.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `fill` attribute of the path.
+    pub fn fill(mut self, fill: &str) -> Self {
+        self.fill = fill.to_string();
+        self
+    }
+
+    /// Set the `stroke` attribute of the path (by default, the path has no stroke).
+    pub fn stroke(mut self, stroke: &str) -> Self {
+        self.stroke = Some(stroke.to_string());
+        self
+    }
+
+    /// Set the `fill-rule` attribute of the path: `evenodd` (the default) or `nonzero`, both fill the outlines without their holes.
+    pub fn fill_rule(mut self, fill_rule: &str) -> Self {
+        self.fill_rule = fill_rule.to_string();
+        self
+    }
+
+    /// Set the `width` and `height` attributes of the document, e.g. `"64"` or `"100%"` (by default, the document has no size).
+    pub fn size(mut self, width: &str, height: &str) -> Self {
+        self.size = Some((width.to_string(), height.to_string()));
+        self
+    }
+
+    /// Add a rectangle of the given color behind the path, covering the `viewBox`.
+    pub fn background(mut self, color: &str) -> Self {
+        self.background = Some(color.to_string());
+        self
+    }
+}
 
 /// A function that takes a 2D array of bits and an option as input and return a string of SVG Path commands as output.
 /// # Examples
@@ -42,7 +512,7 @@ const H_VALUE_FOR_SIGNED:   [i8; 7]       = [-4, 0, -8, 0, -1, 0, -2]; // (idem)
 /// # assert_eq!(bits_to_paths(bits.to_vec(), true), "M1 0H4V1H1ZM6 0H11V5H6ZM0 1H1V4H0ZM4 1H5V4H4ZM7 1V4H10V1ZM8 2H9V3H8ZM1 4H4V5H1Z");
 /// println!("{}", bits_to_paths(bits, true));
 /// ```
-/// - If you plan to reuse the array of bits after using this function, use the `to_vec()` method like this:
+/// - If you plan to reuse the array of bits after using this function, use [`bits_to_paths_ref`] or the `to_vec()` method like this:
 ///
 /// ```edition2018
 /// # use contour_tracing::array::bits_to_paths;
@@ -55,111 +525,2199 @@ const H_VALUE_FOR_SIGNED:   [i8; 7]       = [-4, 0, -8, 0, -1, 0, -2]; // (idem)
 /// println!("{:?}", bits);
 /// ```
 pub fn bits_to_paths(bits: Vec<Vec<i8>>, closepaths: bool) -> String {
-    let rows: usize = bits.len();
-    let cols: usize = bits[0].len();
-    let mut contours = vec![vec![0i8; cols + 2]; rows + 2]; // Add a border of 1 bit to prevent out-of-bounds error
-    for r in 0..=rows - 1_usize {
-        for c in 0..=cols - 1_usize {
-            contours[r + 1][c + 1] = if bits[r][c] == 1 { 1 } else { -1 };
-        }
+    bits_to_paths_ref(&bits, closepaths)
+}
+
+/// Same as [`bits_to_paths`] but the 2D array of bits is borrowed, so it can be reused without being cloned.
+///
+/// The edge cases are the same: an array of empty rows gives an empty string, and an array without any row
+/// or with rows of different lengths panics (see [`try_bits_to_paths`] to handle them).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_ref;
+/// let bits = vec![vec![ 1,0,0 ],
+///                 vec![ 0,1,0 ],
+///                 vec![ 0,0,1 ]];
+///
+/// # assert_eq!(bits_to_paths_ref(&bits, true), "M0 0H1V1H0ZM1 1H2V2H1ZM2 2H3V3H2Z");
+/// println!("{}", bits_to_paths_ref(&bits, true));
+/// println!("{:?}", bits);
+/// ```
+pub fn bits_to_paths_ref(bits: &[Vec<i8>], closepaths: bool) -> String {
+    try_bits_to_paths(bits, closepaths).expect("the array of bits is malformed")
+}
+
+/// Same as [`bits_to_paths_ref`] but a malformed 2D array of bits returns an error instead of panicking, e.g. for an untrusted input.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{try_bits_to_paths, TraceError};
+/// let bits = vec![vec![ 1,0,0 ],
+///                 vec![ 0,1 ]];
+///
+/// assert_eq!(try_bits_to_paths(&bits, true), Err(TraceError::RaggedRows { row: 1, expected: 3, found: 2 }));
+/// assert_eq!(try_bits_to_paths(&[], true), Err(TraceError::Empty));
+/// # assert_eq!(try_bits_to_paths(&bits[..1], true), Ok("M0 0H1V1H0Z".to_string()));
+/// ```
+pub fn try_bits_to_paths(bits: &[Vec<i8>], closepaths: bool) -> Result<String, TraceError> {
+    let expected = bits.first().ok_or(TraceError::Empty)?.len();
+    if let Some((row, found)) = bits.iter().map(Vec::len).enumerate().find(|&(_, found)| found != expected) {
+        return Err(TraceError::RaggedRows { row, expected, found });
     }
-    let mut paths = String::new();
-    let mut ol: usize;
-    let mut hl: usize;
-    for cursor_y in 1..=rows as usize {
-        ol = 0;
-        hl = 0;
-        for cursor_x in 1..=cols as usize {
-            if ol == hl && contours[cursor_y][cursor_x] == 1 {
-                trace_bits(true, cursor_x, cursor_y, [2, 3, 4, 5, 6, 7, 0, 1], 2, (7, 1, 0), O_VERTEX_WITH_BORDER, O_VALUE_FOR_SIGNED, &mut contours, &mut paths, closepaths);
+    Ok(bits_to_paths_with_options(bits, &TraceOptions::new().close_paths(closepaths)))
+}
+
+/// Same as [`bits_to_paths_ref`] but the SVG Path commands are appended to a string, e.g. to reuse its allocation inside a hot loop.
+///
+/// The content of the string is kept: [`clear`](String::clear) it before the call to keep only the new paths.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_into;
+/// let mut paths = String::new();
+///
+/// for bits in [vec![vec![ 1,1 ]], vec![vec![ 1,0,1 ]]] {
+///     paths.clear();
+///     bits_to_paths_into(&bits, true, &mut paths);
+///     println!("{}", paths);
+/// }
+/// # assert_eq!(paths, "M0 0H1V1H0ZM2 0H3V1H2Z");
+/// ```
+pub fn bits_to_paths_into(bits: &[Vec<i8>], closepaths: bool, out: &mut String) {
+    super::grid::scan(&mut bits_grid(bits), |_, points| super::contour::push_path(out, points, closepaths));
+}
+
+/// Same as [`bits_to_paths_ref`] but the SVG Path commands are written into a [`std::fmt::Write`], without an intermediate string.
+///
+/// The tracing stops at the first error of the writer, which is returned.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::write_paths;
+/// let bits = vec![vec![ 1,0,1 ]];
+/// let mut paths = String::from("<path d=\"");
+///
+/// write_paths(&bits, true, &mut paths).unwrap();
+/// paths.push_str("\"/>");
+/// # assert_eq!(paths, "<path d=\"M0 0H1V1H0ZM2 0H3V1H2Z\"/>");
+/// println!("{}", paths);
+/// ```
+pub fn write_paths<W: std::fmt::Write>(bits: &[Vec<i8>], closepaths: bool, out: &mut W) -> std::fmt::Result {
+    let mut result = Ok(());
+    super::grid::scan_while(&mut bits_grid(bits), |_, points| {
+        result = (|| {
+            write!(out, "M{} {}", points[0].0, points[0].1)?;
+            for pair in points.windows(2) {
+                if pair[1].1 == pair[0].1 { write!(out, "H{}", pair[1].0)?; } else { write!(out, "V{}", pair[1].1)?; }
             }
-            else if ol > hl && contours[cursor_y][cursor_x] == -1 {
-                trace_bits(false, cursor_x, cursor_y, [4, 5, 6, 7, 0, 1, 2, 3], -2, (1, 7, 6), H_VERTEX_WITH_BORDER, H_VALUE_FOR_SIGNED, &mut contours, &mut paths, closepaths);
+            if closepaths { out.write_char('Z')?; }
+            Ok(())
+        })();
+        result.is_ok()
+    });
+    result
+}
+
+/// Same as [`bits_to_paths_ref`] but the SVG Path commands of each contour are a separate string, in the order of the scan,
+/// e.g. to give each contour its own `<path>` element. The concatenation of the strings is the string of [`bits_to_paths`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_path_list, bits_to_paths};
+/// let bits = vec![vec![ 1,0,1 ]];
+///
+/// let paths = bits_to_path_list(&bits, true);
+/// # assert_eq!(paths, vec!["M0 0H1V1H0Z", "M2 0H3V1H2Z"]);
+/// # assert_eq!(paths.concat(), bits_to_paths(bits.to_vec(), true));
+/// println!("{:?}", paths);
+/// ```
+pub fn bits_to_path_list(bits: &[Vec<i8>], closepaths: bool) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        let mut path = String::new();
+        super::contour::push_path(&mut path, points, closepaths);
+        paths.push(path);
+    });
+    paths
+}
+
+/// Same as [`bits_to_paths_ref`] but the connected components are traced in parallel, e.g. for a large mask with many blobs.
+///
+/// The 8-connected components of the foreground (the pixels touching by a corner are in the same component, as are
+/// an outline and the holes it borders) are labeled first, then each one is traced on its own thread. The paths of
+/// a component are in scan order and the components are in the order of their top left pixel, so the output is stable
+/// whatever the scheduling of the threads.
+///
+/// The output has the same paths as [`bits_to_paths`] but **not in the same order**: the holes of a component come
+/// right after its outline instead of in the row where the scan finds them, so compare the sets of paths, not the strings.
+/// An array without any row gives an empty string.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths, bits_to_paths_parallel};
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// // The hole comes before the second outline in parallel, after it in scan order
+/// assert_eq!(bits_to_paths_parallel(&bits, true), "M0 0H3V3H0ZM1 1V2H2V1ZM4 0H5V1H4Z");
+/// assert_eq!(bits_to_paths(bits.to_vec(), true), "M0 0H3V3H0ZM4 0H5V1H4ZM1 1V2H2V1Z");
+/// ```
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub fn bits_to_paths_parallel(bits: &[Vec<i8>], closepaths: bool) -> String {
+    use rayon::prelude::*;
+    let (rows, cols) = (bits.len(), bits.first().map_or(0, Vec::len));
+    // The label of each pixel (0 for the background) and the bounding box of each component: min row, min col, max row, max col
+    let mut labels = vec![0usize; rows * cols];
+    let mut components: Vec<(usize, usize, usize, usize)> = Vec::new();
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            if bits[r][c] != 1 || labels[r * cols + c] != 0 {
+                continue;
             }
-            match contours[cursor_y][cursor_x].abs() {
-                2 |   4 |  10 |  12 => if contours[cursor_y][cursor_x] > 0 { ol += 1 } else { hl += 1 },
-                5 |   7 |  13 |  15 => if contours[cursor_y][cursor_x] > 0 { ol -= 1 } else { hl -= 1 },
-                _ => ()
+            components.push((r, c, r, c));
+            let (label, bbox) = (components.len(), components.last_mut().unwrap());
+            labels[r * cols + c] = label;
+            stack.push((r, c));
+            while let Some((r, c)) = stack.pop() {
+                *bbox = (bbox.0.min(r), bbox.1.min(c), bbox.2.max(r), bbox.3.max(c));
+                for nr in r.saturating_sub(1)..(r + 2).min(rows) {
+                    for nc in c.saturating_sub(1)..(c + 2).min(cols) {
+                        if bits[nr][nc] == 1 && labels[nr * cols + nc] == 0 {
+                            labels[nr * cols + nc] = label;
+                            stack.push((nr, nc));
+                        }
+                    }
+                }
             }
         }
     }
+    components.par_iter().enumerate().map(|(i, &(r0, c0, r1, c1))| {
+        let mut paths = String::new();
+        let mut contours = super::grid::bordered(r1 - r0 + 1, c1 - c0 + 1, |r, c| labels[(r0 + r) * cols + c0 + c] == i + 1);
+        let mut shifted: Vec<(i32, i32)> = Vec::new();
+        super::grid::scan(&mut contours, |_, points| {
+            shifted.clear();
+            shifted.extend(points.iter().map(|p| (p.0 + c0 as i32, p.1 + r0 as i32)));
+            super::contour::push_path(&mut paths, &shifted, closepaths);
+        });
+        paths
+    }).collect::<Vec<String>>().concat()
+}
+
+/// Same as [`bits_to_paths_ref`] but the bounding box of all the vertices is also returned, e.g. for the `viewBox` attribute
+/// of an SVG, or `None` if there is no contour (the string is then empty).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_with_bbox;
+/// # use contour_tracing::contour::BBox;
+/// let bits = vec![vec![ 0,0,0 ],
+///                 vec![ 0,1,1 ]];
+///
+/// let (paths, bbox) = bits_to_paths_with_bbox(&bits, true);
+/// # assert_eq!((paths.as_str(), bbox), ("M1 1H3V2H1Z", Some(BBox { min_x: 1, min_y: 1, max_x: 3, max_y: 2 })));
+/// println!("{} {:?}", paths, bbox);
+/// ```
+pub fn bits_to_paths_with_bbox(bits: &[Vec<i8>], closepaths: bool) -> (String, Option<super::contour::BBox>) {
+    let mut paths = String::new();
+    let mut bbox: Option<super::contour::BBox> = None;
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        super::contour::push_path(&mut paths, points, closepaths);
+        for &(x, y) in points {
+            bbox = Some(match bbox {
+                Some(b) => super::contour::BBox { min_x: b.min_x.min(x), min_y: b.min_y.min(y), max_x: b.max_x.max(x), max_y: b.max_y.max(y) },
+                None => super::contour::BBox { min_x: x, min_y: y, max_x: x, max_y: y },
+            });
+        }
+    });
+    (paths, bbox)
+}
+
+/// A function that takes a 2D array of bits, an option and a connectivity as input and return a string of SVG Path commands as output.
+///
+/// With [`Connectivity::Four`], the paths are the ones of [`bits_to_paths`]. With [`Connectivity::Eight`], the bits touching only
+/// by a corner are traced as one contour, e.g. for the strokes of the characters of OCR masks: the path then touches itself at the
+/// corner, which is expressed as the same vertex twice (two separate commands ending at the same coordinates), and the background
+/// bits touching only by a corner are in different holes.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_with, Connectivity};
+/// let bits = vec![vec![ 1,0 ],
+///                 vec![ 0,1 ]];
+///
+/// # assert_eq!(bits_to_paths_with(&bits, true, Connectivity::Four), "M0 0H1V1H0ZM1 1H2V2H1Z");
+/// # assert_eq!(bits_to_paths_with(&bits, true, Connectivity::Eight), "M0 0H1V1H2V2H1V1H0Z");
+/// println!("{}", bits_to_paths_with(&bits, true, Connectivity::Eight));
+/// ```
+pub fn bits_to_paths_with(bits: &[Vec<i8>], closepaths: bool, connectivity: Connectivity) -> String {
+    bits_to_paths_with_options(bits, &TraceOptions::new().close_paths(closepaths).connectivity(connectivity))
+}
+
+/// A function that takes a 2D array of bytes, a foreground value and an option as input and return a string of SVG Path commands as output.
+///
+/// The bytes equal to `foreground` are the foreground, the other bytes are the background, so the masks of **0** / **1**
+/// or **0** / **255** bytes can be traced without a conversion to `i8`.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bytes_to_paths;
+/// let bytes = vec![vec![ 255,  0,  0 ],
+///                  vec![   0,255,  0 ],
+///                  vec![   0,  0,255 ]];
+///
+/// # assert_eq!(bytes_to_paths(bytes.to_vec(), 255, true), "M0 0H1V1H0ZM1 1H2V2H1ZM2 2H3V3H2Z");
+/// println!("{}", bytes_to_paths(bytes, 255, true));
+/// ```
+pub fn bytes_to_paths(bits: Vec<Vec<u8>>, foreground: u8, closepaths: bool) -> String {
+    let mut paths = String::new();
+    let mut contours = super::grid::bordered_par(bits.len(), bits[0].len(), |r, c| bits[r][c] == foreground);
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// Same as [`bits_to_paths_ref`] but every nonzero bit is the foreground (not only the **1** bits), e.g. for the masks
+/// of **0** / **-1** or **0** / **7** values.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_nonzero_to_paths, bits_to_paths};
+/// let bits = vec![vec![ 7,0 ],
+///                 vec![ 7,-1 ]];
+///
+/// # assert_eq!(bits_nonzero_to_paths(&bits, true), "M0 0H1V1H2V2H0Z");
+/// # assert_eq!(bits_to_paths(bits.to_vec(), true), "");
+/// println!("{}", bits_nonzero_to_paths(&bits, true));
+/// ```
+pub fn bits_nonzero_to_paths(bits: &[Vec<i8>], closepaths: bool) -> String {
+    mask_to_paths(bits, |&bit| bit != 0, closepaths)
+}
+
+/// A function that takes a 2D array of booleans and an option as input and return a string of SVG Path commands as output.
+///
+/// The `true` cells are the foreground, so a boolean mask can be traced without a conversion to `i8`.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bools_to_paths;
+/// let bits = vec![vec![ true,false ],
+///                 vec![ true,true  ]];
+///
+/// # assert_eq!(bools_to_paths(&bits, true), "M0 0H1V1H2V2H0Z");
+/// println!("{}", bools_to_paths(&bits, true));
+/// ```
+pub fn bools_to_paths(bits: &[Vec<bool>], closepaths: bool) -> String {
+    mask_to_paths(bits, |&bit| bit, closepaths)
+}
+
+/// A function that takes a 2D array of cells of any type, a foreground test and an option as input and return a string
+/// of SVG Path commands as output.
+///
+/// The cells for which `is_fg` returns `true` are the foreground, whatever their type, e.g. the pixels above a threshold
+/// or the cells of a given class.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::mask_to_paths;
+/// let grid = vec![vec![ 0.9,0.1,0.7 ]];
+///
+/// # assert_eq!(mask_to_paths(&grid, |&v| v > 0.5, true), "M0 0H1V1H0ZM2 0H3V1H2Z");
+/// println!("{}", mask_to_paths(&grid, |&v| v > 0.5, true));
+/// ```
+pub fn mask_to_paths<T: Sync, F: Fn(&T) -> bool + Sync>(grid: &[Vec<T>], is_fg: F, closepaths: bool) -> String {
+    let mut paths = String::new();
+    let mut contours = super::grid::bordered_par(grid.len(), grid[0].len(), |r, c| is_fg(&grid[r][c]));
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes a flat row-major array of bytes, its width, its height and an option as input and return a string of SVG Path commands as output.
+///
+/// The byte of the pixel `(x, y)` is `bits[y * width + x]` and the non-zero bytes are the foreground, so the paths are the same as
+/// those of [`bits_to_paths`] with the nested rows, without their allocation. An empty string is returned if `width` or `height` is **0**.
+/// The length of the array must be `width * height` (debug builds assert it).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::flat_bits_to_paths;
+/// let bits = vec![ 1,0,0,
+///                  0,1,0 ];
+///
+/// # assert_eq!(flat_bits_to_paths(&bits, 3, 2, true), "M0 0H1V1H0ZM1 1H2V2H1Z");
+/// println!("{}", flat_bits_to_paths(&bits, 3, 2, true));
+/// ```
+pub fn flat_bits_to_paths(bits: &[u8], width: usize, height: usize, closepaths: bool) -> String {
+    debug_assert_eq!(bits.len(), width * height);
+    let mut paths = String::new();
+    if width == 0 || height == 0 {
+        return paths;
+    }
+    let mut contours = super::grid::bordered_par(height, width, |r, c| bits[r * width + c] != 0);
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
     paths
 }
 
-fn trace_bits(outline: bool, cursor_x: usize, cursor_y: usize, mut o: [usize; 8], rot: i8, viv: (usize, usize, usize), vertex: [(i8, i8); 7], value: [i8; 7], contours: &mut Vec<Vec<i8>>, paths: &mut String, closepaths: bool) {
-    let mut tracer_x = cursor_x;
-    let mut tracer_y = cursor_y;
-    let mut vertices_nbr: usize = 1;
-    paths.push_str(&format!("M{} {}", tracer_x.wrapping_add(vertex[o[0]].0 as usize), tracer_y.wrapping_add(vertex[o[0]].1 as usize)));
-    let mut neighbors: [i8; 8];
-    let mut rn: u8;
-    loop {
-        neighbors = [
-            contours[tracer_y - 1][tracer_x    ],
-            contours[tracer_y - 1][tracer_x + 1],
-            contours[tracer_y    ][tracer_x + 1],
-            contours[tracer_y + 1][tracer_x + 1],
-            contours[tracer_y + 1][tracer_x    ],
-            contours[tracer_y + 1][tracer_x - 1],
-            contours[tracer_y    ][tracer_x - 1],
-            contours[tracer_y - 1][tracer_x - 1]
-        ];
-        rn =
-            if outline {
-                if      neighbors[o[7]] > 0 && neighbors[o[0]] > 0 { 1 }
-                else if neighbors[o[0]] > 0                        { 2 }
-                else if neighbors[o[1]] > 0 && neighbors[o[2]] > 0 { 3 }
-                else { 0 }
-            }
-            else if neighbors[o[1]] < 0 && neighbors[o[0]] < 0 { 1 }
-            else if neighbors[o[0]] < 0                        { 2 }
-            else if neighbors[o[7]] < 0 && neighbors[o[6]] < 0 { 3 }
-            else { 0 };
-        match rn {
-            1 => {
-                contours[tracer_y][tracer_x] += value[o[0]];
-                tracer_x = tracer_x.wrapping_add(super::MN[o[viv.0]].0 as usize);
-                tracer_y = tracer_y.wrapping_add(super::MN[o[viv.0]].1 as usize);
-                o.rotate_right(rot.rem_euclid(8) as usize); // Rotate 90 degrees, counterclockwise for the outlines (rot = 2) or clockwise for the holes (rot = -2)
-                vertices_nbr += 1;
-                if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as usize))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as usize))); }
-            }
-            2 => {
-                contours[tracer_y][tracer_x] += value[o[0]];
-                tracer_x = tracer_x.wrapping_add(super::MN[o[0]].0 as usize);
-                tracer_y = tracer_y.wrapping_add(super::MN[o[0]].1 as usize);
-            }
-            3 => {
-                contours[tracer_y][tracer_x] += value[o[0]];
-                o.rotate_left(rot.rem_euclid(8) as usize); // Rotate 90 degrees, clockwise for the outlines (rot = 2) or counterclockwise for the holes (rot = -2)
-                contours[tracer_y][tracer_x] += value[o[0]];
-                vertices_nbr += 1;
-                if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as usize))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as usize))); }
-                o.rotate_right(rot.rem_euclid(8) as usize);
-                tracer_x = tracer_x.wrapping_add(super::MN[o[viv.1]].0 as usize);
-                tracer_y = tracer_y.wrapping_add(super::MN[o[viv.1]].1 as usize);
-                vertices_nbr += 1;
-                if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as usize))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as usize))); }
-            }
-            _ => {
-                contours[tracer_y][tracer_x] += value[o[0]];
-                o.rotate_left(rot.rem_euclid(8) as usize);
-                vertices_nbr += 1;
-                if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as usize))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as usize))); }
+/// A function that takes a 2D array of optional values and an option as input and return a string of SVG Path commands as output.
+///
+/// The `Some` values are the foreground and the `None` values are the background, whatever the values are,
+/// e.g. for a labeled grid with missing data. See [`some_labels_to_paths`] to trace each value separately.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::some_to_paths;
+/// let grid = vec![vec![ Some(1), Some(2), None ]];
+///
+/// # assert_eq!(some_to_paths(&grid, true), "M0 0H2V1H0Z");
+/// println!("{}", some_to_paths(&grid, true));
+/// ```
+pub fn some_to_paths<T: Sync>(grid: &[Vec<Option<T>>], closepaths: bool) -> String {
+    let mut paths = String::new();
+    let mut contours = super::grid::bordered_par(grid.len(), grid[0].len(), |r, c| grid[r][c].is_some());
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes a 2D array of optional values and an option as input and return, for each distinct `Some` value (label),
+/// a string of SVG Path commands as output.
+///
+/// The paths of a label are the contours of the cells with this label (the other labels and the `None` values are
+/// the background), each label is traced within its own bounding box.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::some_labels_to_paths;
+/// let grid = vec![vec![ Some('a'), Some('b'), None ]];
+///
+/// let labels = some_labels_to_paths(&grid, true);
+/// # assert_eq!(labels[&'a'], "M0 0H1V1H0Z");
+/// # assert_eq!(labels[&'b'], "M1 0H2V1H1Z");
+/// println!("{:?}", labels);
+/// ```
+pub fn some_labels_to_paths<T: Ord + Clone + Sync>(grid: &[Vec<Option<T>>], closepaths: bool) -> BTreeMap<T, String> {
+    let mut bboxes: BTreeMap<&T, (usize, usize, usize, usize)> = BTreeMap::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, label) in row.iter().enumerate() {
+            if let Some(label) = label {
+                let b = bboxes.entry(label).or_insert((x, y, x, y));
+                *b = (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y));
             }
         }
-        if tracer_x == cursor_x && tracer_y == cursor_y && vertices_nbr > 2 {
-            break;
-        }
     }
-    loop {
-        contours[tracer_y][tracer_x] += value[o[0]];
-        if o[0] == viv.2 {
-            break;
+    let mut labels = BTreeMap::new();
+    for (label, (x0, y0, x1, y1)) in bboxes {
+        let mut contours = super::grid::bordered_par(y1 - y0 + 1, x1 - x0 + 1, |r, c| grid[y0 + r][x0 + c].as_ref() == Some(label));
+        let mut paths = String::new();
+        let mut shifted: Vec<(i32, i32)> = Vec::new();
+        super::grid::scan(&mut contours, |_, points| {
+            shifted.clear();
+            shifted.extend(points.iter().map(|p| (p.0 + x0 as i32, p.1 + y0 as i32)));
+            super::contour::push_path(&mut paths, &shifted, closepaths);
+        });
+        labels.insert(label.clone(), paths);
+    }
+    labels
+}
+
+/// A function that takes a 2D array of bits and some options as input and return a string of SVG Path commands as output.
+/// # Examples
+/// ```ignore
+/// use contour_tracing::array::{bits_to_paths_with_options, ScanOrder, TraceOptions};
+/// ```
+/// - Scan a large array of bits by tiles of 64x64 bits, the output is the same as with the default scan order:
+///
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_with_options, ScanOrder, TraceOptions};
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let options = TraceOptions::new().close_paths(true).scan_order(ScanOrder::Morton(64));
+/// # assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H3V3H0ZM1 1V2H2V1Z");
+/// println!("{}", bits_to_paths_with_options(&bits, &options));
+/// ```
+/// - With the relative SVG Path commands:
+///
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_with_options, TraceOptions};
+/// # let bits = vec![vec![ 1,1,1 ],
+/// #                 vec![ 1,0,1 ],
+/// #                 vec![ 1,1,1 ]];
+/// let options = TraceOptions::new().close_paths(true).relative_coordinates(true);
+/// # assert_eq!(bits_to_paths_with_options(&bits, &options), "m0 0h3v3h-3zm1 1v1h1v-1z");
+/// println!("{}", bits_to_paths_with_options(&bits, &options));
+/// ```
+pub fn bits_to_paths_with_options(bits: &[Vec<i8>], options: &TraceOptions) -> String {
+    let mut paths = String::with_capacity(path_len(bits, bits.len() / 64 + 1)); // A rough estimate from about 64 rows
+    let mut current = (0, 0);
+    let (corner, opposite) = options.output_frame(bits.len(), bits.first().map_or(0, |row| row.len()));
+    trace_with_options(bits, options, |_, _, points| {
+        if options.frame_boundary == super::contour::FrameBoundary::Open {
+            if let Some(runs) = super::contour::frame_runs(points, corner, opposite) {
+                for run in runs {
+                    if options.relative {
+                        super::contour::push_relative_path(&mut paths, &run, false, &mut current);
+                    } else {
+                        super::contour::push_path(&mut paths, &run, false);
+                    }
+                }
+                return;
+            }
+        }
+        if options.relative {
+            super::contour::push_relative_path(&mut paths, points, options.closepaths, &mut current);
+        } else {
+            match options.corner_style {
+                CornerStyle::Chamfer(length) if length > 0.0 => push_styled_path(&mut paths, points, options.closepaths, length, false, options.precision),
+                CornerStyle::Round(radius) if radius > 0.0 => push_styled_path(&mut paths, points, options.closepaths, radius, true, options.precision),
+                _ => super::contour::push_path(&mut paths, points, options.closepaths),
+            }
+        }
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits as input and return a vec of contours as output.
+///
+/// The contours are the ones of [`bits_to_paths`], in the same order: the vertices of the outlines are clockwise and the vertices
+/// of the holes are counterclockwise, so they can be given to a geometry engine without parsing the SVG Path commands.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::{array::bits_to_contours, contour::Contour};
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let contours = bits_to_contours(&bits);
+/// # assert_eq!(contours, vec![Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false), Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true)]);
+/// println!("{:?}", contours);
+/// ```
+pub fn bits_to_contours(bits: &[Vec<i8>]) -> Vec<super::contour::Contour> {
+    let mut contours: Vec<super::contour::Contour> = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| contours.push(super::contour::Contour::new(points.to_vec(), !outline)));
+    contours
+}
+
+/// A function that takes a 2D array of bits as input and return the outline/hole hierarchy of the contours as output.
+///
+/// Each top-level outline is an [`OutlineNode`](super::contour::OutlineNode) with its holes, and each hole has the outlines
+/// inside it (the islands), and so on: the outlines are in scan order, and so are the holes of an outline and the outlines of a hole.
+/// The ids of the outlines are their indexes in scan order among the outlines.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_hierarchy;
+/// let bits = vec![vec![ 1,1,1,1,1 ],
+///                 vec![ 1,0,0,0,1 ],
+///                 vec![ 1,0,1,0,1 ],
+///                 vec![ 1,0,0,0,1 ],
+///                 vec![ 1,1,1,1,1 ]];
+///
+/// let nodes = bits_to_hierarchy(&bits);
+/// # assert_eq!(nodes.len(), 1);
+/// # assert_eq!(nodes[0].holes[0].outlines[0].outline.points, vec![(2, 2), (3, 2), (3, 3), (2, 3)]);
+/// println!("{:?}", nodes);
+/// ```
+pub fn bits_to_hierarchy(bits: &[Vec<i8>]) -> Vec<super::contour::OutlineNode> {
+    super::contour::hierarchy(bits_to_contours(bits))
+}
+
+/// A function that takes a 2D array of bits as input and return the polygons of all the connected components as output:
+/// each outline is a closed exterior ring with the closed interior rings of its holes, and the outlines inside the holes
+/// (the islands) are separate polygons (see [`OutlineNode::polygon_rings`](super::contour::OutlineNode::polygon_rings)).
+///
+/// The polygons are plain rings of vertices, e.g. for the GeoJSON of `bits_to_geojson` with the geojson feature;
+/// see `bits_to_multipolygon` for a `geo_types::MultiPolygon` with the geo feature.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_polygon_rings;
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// let polygons = bits_to_polygon_rings(&bits);
+/// # assert_eq!(polygons, vec![
+/// #     (vec![(0, 0), (3, 0), (3, 3), (0, 3), (0, 0)], vec![vec![(1, 1), (1, 2), (2, 2), (2, 1), (1, 1)]]),
+/// #     (vec![(4, 0), (5, 0), (5, 1), (4, 1), (4, 0)], vec![]),
+/// # ]);
+/// println!("{:?}", polygons);
+/// ```
+pub fn bits_to_polygon_rings(bits: &[Vec<i8>]) -> Vec<super::contour::PolygonRings> {
+    bits_to_hierarchy(bits).iter().flat_map(|node| node.polygon_rings()).collect()
+}
+
+/// A function that takes a 2D array of bits as input and return a `geo_types::MultiPolygon` as output, e.g. for the algorithms of the `geo` crate.
+///
+/// The polygons are the ones of [`bits_to_polygon_rings`]: each outline is a polygon with its holes as interior rings, and the
+/// outlines inside the holes (the islands) are separate polygons. The winding follows the conventions of the `geo` crate:
+/// the coordinates are kept, and there the outlines are counterclockwise (a positive signed area, as in a y-up space) and
+/// the holes clockwise, so the rings are not reversed.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_multipolygon;
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// let multipolygon = bits_to_multipolygon(&bits);
+/// # assert_eq!(multipolygon.0.len(), 2);
+/// # assert_eq!(multipolygon.0[0].interiors().len(), 1);
+/// # assert_eq!(multipolygon.0[1].exterior().0[1], geo_types::Coord { x: 5.0, y: 0.0 });
+/// println!("{:?}", multipolygon);
+/// ```
+#[cfg(feature = "geo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+pub fn bits_to_multipolygon(bits: &[Vec<i8>]) -> geo_types::MultiPolygon<f64> {
+    geo_types::MultiPolygon(bits_to_polygon_rings(bits).into_iter().map(super::contour::geo_polygon).collect())
+}
+
+/// A function that takes a 2D array of bits as input and return a GeoJSON `FeatureCollection` as output.
+///
+/// Each feature is a `Polygon` of [`bits_to_polygon_rings`]: its exterior ring and the rings of its holes, closed and
+/// with the winding of the GeoJSON specification (RFC 7946), with the `[x, y]` coordinates of the vertices. The properties
+/// of a feature are `is_hole` (always `false`: the holes are the interior rings) and the `area` of the polygon without its holes.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_geojson;
+/// let bits = vec![vec![ 1,1 ]];
+///
+/// # assert_eq!(bits_to_geojson(&bits), concat!(r#"{"type":"FeatureCollection","features":[{"type":"Feature","#,
+/// #     r#""geometry":{"type":"Polygon","coordinates":[[[0,0],[2,0],[2,1],[0,1],[0,0]]]},"properties":{"is_hole":false,"area":2}}]}"#));
+/// println!("{}", bits_to_geojson(&bits));
+/// ```
+#[cfg(feature = "geojson")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+pub fn bits_to_geojson(bits: &[Vec<i8>]) -> String {
+    let ring = |points: &[(i32, i32)]| format!("[{}]", points.iter().map(|p| format!("[{},{}]", p.0, p.1)).collect::<Vec<String>>().join(","));
+    let features: Vec<String> = bits_to_polygon_rings(bits).iter().map(|(exterior, interiors)| {
+        let area = super::contour::ring_signed_area(exterior) + interiors.iter().map(|r| super::contour::ring_signed_area(r)).sum::<i64>();
+        let rings: Vec<String> = std::iter::once(exterior).chain(interiors).map(|r| ring(r)).collect();
+        format!(r#"{{"type":"Feature","geometry":{{"type":"Polygon","coordinates":[{}]}},"properties":{{"is_hole":false,"area":{}}}}}"#, rings.join(","), area)
+    }).collect();
+    format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(","))
+}
+
+/// A function that takes a 2D array of bits as input and return the metadata of each contour as output,
+/// in the order of [`bits_to_contours`], e.g. to filter the contours by area without tracing them again.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_contours_meta;
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// let meta = bits_to_contours_meta(&bits);
+/// # assert_eq!(meta.iter().map(|m| (m.signed_area, m.is_hole, m.vertex_count)).collect::<Vec<_>>(), vec![(9.0, false, 4), (1.0, false, 4), (-1.0, true, 4)]);
+/// let large = meta.iter().filter(|m| m.signed_area.abs() > 1.0).count();
+/// # assert_eq!(large, 1);
+/// ```
+pub fn bits_to_contours_meta(bits: &[Vec<i8>]) -> Vec<super::contour::ContourMeta> {
+    bits_to_contours(bits).iter().map(|contour| super::contour::ContourMeta {
+        signed_area: contour.signed_area() as f64,
+        is_hole: contour.is_hole,
+        vertex_count: contour.points.len(),
+        bbox: contour.bbox().unwrap_or_default(),
+        principal_endpoints: contour.principal_endpoints(),
+    }).collect()
+}
+
+/// A function that takes a 2D array of bits and some options as input and return a vec of contours as output.
+///
+/// The contours are the ones of [`bits_to_paths_with_options`] with the same options, in the same order, and the
+/// [`explicitly_closed`](super::contour::Contour::explicitly_closed) field of each contour is the **closepaths option**.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_contours_with_options, TraceOptions};
+/// let bits = vec![vec![ 1,1 ]];
+///
+/// let contours = bits_to_contours_with_options(&bits, &TraceOptions::new().close_paths(true));
+/// # assert_eq!(contours[0].points, vec![(0, 0), (2, 0), (2, 1), (0, 1)]);
+/// # assert!(contours[0].explicitly_closed);
+/// println!("{:?}", contours);
+/// ```
+pub fn bits_to_contours_with_options(bits: &[Vec<i8>], options: &TraceOptions) -> Vec<super::contour::Contour> {
+    let mut contours: Vec<super::contour::Contour> = Vec::new();
+    trace_with_options(bits, options, |_, is_hole, points| {
+        let mut contour = super::contour::Contour::new(points.to_vec(), is_hole);
+        contour.explicitly_closed = options.closepaths;
+        contours.push(contour);
+    });
+    contours
+}
+
+/// A function that takes a 2D array of bits and some options as input and return a vec of contours with their ids as output.
+///
+/// The contours are the ones of [`bits_to_contours_with_options`], and their ids are assigned in scan order (0, 1, 2...),
+/// so an id is a stable key of a contour within a trace. With [`OutputOrder::Canonical`], the contours are reordered
+/// but keep the ids of the scan order.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_contours_with_ids, OutputOrder, TraceOptions};
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// let options = TraceOptions::new().output_order(OutputOrder::Canonical);
+/// let ids: Vec<usize> = bits_to_contours_with_ids(&bits, &options).iter().map(|(id, _)| *id).collect();
+/// # assert_eq!(ids, vec![0, 2, 1]);
+/// println!("{:?}", ids);
+/// ```
+pub fn bits_to_contours_with_ids(bits: &[Vec<i8>], options: &TraceOptions) -> Vec<(usize, super::contour::Contour)> {
+    let mut contours: Vec<(usize, super::contour::Contour)> = Vec::new();
+    trace_with_options(bits, options, |id, is_hole, points| {
+        let mut contour = super::contour::Contour::new(points.to_vec(), is_hole);
+        contour.explicitly_closed = options.closepaths;
+        contours.push((id, contour));
+    });
+    contours
+}
+
+/// The template of [`bits_to_path_elements`] for an SVG `<path>` element with a `data-id` attribute.
+pub const PATH_ELEMENT_TEMPLATE: &str = "<path d=\"{d}\" data-id=\"{id}\"/>";
+
+/// A function that takes a 2D array of bits, some options and a template as input and return a vec of strings,
+/// one for each contour, as output.
+///
+/// Each string is the `template` where `{id}` is replaced by the id of the contour (as in [`bits_to_contours_with_ids`]),
+/// `{d}` by its SVG Path commands (as in [`bits_to_paths_with_options`], the relative coordinates of each contour
+/// start from the origin) and `{length}` by the length of the path (as in [`bits_to_paths_with_lengths`]),
+/// e.g. with [`PATH_ELEMENT_TEMPLATE`] to map the clicks on an interactive SVG back to the contours, or with
+/// `<path d="{d}" pathLength="{length}"/>` for the stroke animations.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_path_elements, TraceOptions, PATH_ELEMENT_TEMPLATE};
+/// let bits = vec![vec![ 1,0,1 ]];
+///
+/// let elements = bits_to_path_elements(&bits, &TraceOptions::new().close_paths(true), PATH_ELEMENT_TEMPLATE);
+/// # assert_eq!(elements, vec![r#"<path d="M0 0H1V1H0Z" data-id="0"/>"#, r#"<path d="M2 0H3V1H2Z" data-id="1"/>"#]);
+/// println!("{:?}", elements);
+/// ```
+pub fn bits_to_path_elements(bits: &[Vec<i8>], options: &TraceOptions, template: &str) -> Vec<String> {
+    let mut elements: Vec<String> = Vec::new();
+    trace_separate_paths(bits, options, |id, paths, length| {
+        elements.push(template.replace("{id}", &id.to_string()).replace("{length}", &length.to_string()).replace("{d}", &paths));
+    });
+    elements
+}
+
+/// A function that takes a 2D array of bits, an option and some document options as input and return a complete SVG document as output.
+///
+/// The document has a single `<path>` element with the paths of [`bits_to_paths`] and the attributes of the options,
+/// and its `viewBox` is the bounding box of the contours (see [`bits_to_paths_with_bbox`]), or the whole array of bits
+/// if there is no contour. The values of the attributes are escaped.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_svg_document, SvgDocOptions};
+/// let bits = vec![vec![ 0,0,0 ],
+///                 vec![ 0,1,1 ]];
+///
+/// let svg = bits_to_svg_document(&bits, true, &SvgDocOptions::new().fill("red"));
+/// # assert_eq!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="1 1 2 1"><path d="M1 1H3V2H1Z" fill="red" fill-rule="evenodd"/></svg>"#);
+/// println!("{}", svg);
+/// ```
+pub fn bits_to_svg_document(bits: &[Vec<i8>], closepaths: bool, options: &SvgDocOptions) -> String {
+    let escape = |value: &str| value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;");
+    let (paths, bbox) = bits_to_paths_with_bbox(bits, closepaths);
+    let bbox = bbox.unwrap_or(super::contour::BBox { min_x: 0, min_y: 0, max_x: bits.first().map_or(0, |row| row.len()) as i32, max_y: bits.len() as i32 });
+    let (x, y, width, height) = (bbox.min_x, bbox.min_y, bbox.width(), bbox.height());
+    let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}""#, x, y, width, height);
+    if let Some((w, h)) = &options.size {
+        svg.push_str(&format!(r#" width="{}" height="{}""#, escape(w), escape(h)));
+    }
+    svg.push('>');
+    if let Some(color) = &options.background {
+        svg.push_str(&format!(r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#, x, y, width, height, escape(color)));
+    }
+    svg.push_str(&format!(r#"<path d="{}" fill="{}""#, paths, escape(&options.fill)));
+    if let Some(stroke) = &options.stroke {
+        svg.push_str(&format!(r#" stroke="{}""#, escape(stroke)));
+    }
+    svg.push_str(&format!(r#" fill-rule="{}"/></svg>"#, escape(&options.fill_rule)));
+    svg
+}
+
+/// A function that takes a 2D array of bits and some options as input and return a vec of strings of SVG Path commands,
+/// one for each contour, with their lengths as output.
+///
+/// The paths are the ones of [`bits_to_path_elements`] and the length of a path is the total length of its segments
+/// (the perimeter of the contour if the paths are closed, without its last edge otherwise), e.g. to set the `pathLength`
+/// attribute or the `stroke-dasharray` property of a stroke animation.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_with_lengths, TraceOptions};
+/// let bits = vec![vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_paths_with_lengths(&bits, &TraceOptions::new().close_paths(true)), vec![("M0 0H3V1H0Z".to_string(), 8)]);
+/// # assert_eq!(bits_to_paths_with_lengths(&bits, &TraceOptions::new()), vec![("M0 0H3V1H0".to_string(), 7)]);
+/// println!("{:?}", bits_to_paths_with_lengths(&bits, &TraceOptions::new().close_paths(true)));
+/// ```
+pub fn bits_to_paths_with_lengths(bits: &[Vec<i8>], options: &TraceOptions) -> Vec<(String, u64)> {
+    let mut paths: Vec<(String, u64)> = Vec::new();
+    trace_separate_paths(bits, options, |_, d, length| paths.push((d, length)));
+    paths
+}
+
+// Trace an array of bits and call f(id, path, length) with a separate path for each contour
+fn trace_separate_paths<F: FnMut(usize, String, u64)>(bits: &[Vec<i8>], options: &TraceOptions, mut f: F) {
+    trace_with_options(bits, options, |id, _, points| {
+        let mut paths = String::new();
+        if options.relative {
+            super::contour::push_relative_path(&mut paths, points, options.closepaths, &mut (0, 0));
+        } else {
+            super::contour::push_path(&mut paths, points, options.closepaths);
+        }
+        let n = points.len();
+        let edges = if options.closepaths { n } else { n - 1 };
+        let length = (0..edges).map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            ((b.0 - a.0).abs() + (b.1 - a.1).abs()) as u64 // The edges are horizontal or vertical
+        }).sum();
+        f(id, paths, length);
+    });
+}
+
+/// A function that takes a 2D array of bits, some options, the bounds of a document and a policy as input and
+/// return a string of SVG Path commands, whose coordinates are all within the bounds, as output.
+///
+/// The paths are the ones of [`bits_to_paths_with_options`], and every vertex is guaranteed to be in `[0, width] x [0, height]`
+/// (the vertices are on the sides of the pixels `[0, width) x [0, height)`), e.g. when the bounds of the document are smaller
+/// than the array of bits, or with [`TraceOptions::quantize`] which can move the vertices outside. The vertices outside
+/// are clamped or an error is returned, as selected by the [`BoundsPolicy`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_within_bounds, BoundsPolicy, OutOfBounds, TraceOptions};
+/// let bits = vec![vec![ 0,1,1,1 ],
+///                 vec![ 0,1,1,1 ]];
+/// let options = TraceOptions::new().close_paths(true);
+///
+/// # assert_eq!(bits_to_paths_within_bounds(&bits, &options, 3, 1, BoundsPolicy::Clamp), Ok("M1 0H3V1H1Z".to_string()));
+/// # assert_eq!(bits_to_paths_within_bounds(&bits, &options, 3, 1, BoundsPolicy::Error), Err(OutOfBounds { id: 0, point: (4, 0) }));
+/// println!("{:?}", bits_to_paths_within_bounds(&bits, &options, 3, 1, BoundsPolicy::Clamp));
+/// ```
+pub fn bits_to_paths_within_bounds(bits: &[Vec<i8>], options: &TraceOptions, width: u32, height: u32, policy: BoundsPolicy) -> Result<String, OutOfBounds> {
+    let (width, height) = (width.min(i32::MAX as u32) as i32, height.min(i32::MAX as u32) as i32);
+    let mut paths = String::new();
+    let mut current = (0, 0);
+    let mut clamped: Vec<(i32, i32)> = Vec::new();
+    let mut error: Option<OutOfBounds> = None;
+    trace_with_options(bits, options, |id, _, points| {
+        let outside = points.iter().find(|p| p.0 < 0 || p.1 < 0 || p.0 > width || p.1 > height);
+        let points = match (outside, policy) {
+            (None, _) => points,
+            (Some(&point), BoundsPolicy::Error) => {
+                error = error.or(Some(OutOfBounds { id, point }));
+                return;
+            }
+            (Some(_), BoundsPolicy::Clamp) => {
+                clamped.clear();
+                clamped.extend(points.iter().map(|p| (p.0.max(0).min(width), p.1.max(0).min(height))));
+                merge_collinear(&mut clamped);
+                if clamped.len() < 3 {
+                    return;
+                }
+                &clamped[..]
+            }
+        };
+        if options.relative {
+            super::contour::push_relative_path(&mut paths, points, options.closepaths, &mut current);
+        } else {
+            super::contour::push_path(&mut paths, points, options.closepaths);
+        }
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(paths),
+    }
+}
+
+/*
+ Trace an array of bits and call f(id, is_hole, vertices) for each contour selected, ordered and normalized as set in the options,
+ the ids of the selected contours are given in scan order
+*/
+fn trace_with_options<F: FnMut(usize, bool, &[(i32, i32)])>(bits: &[Vec<i8>], options: &TraceOptions, mut f: F) {
+    let mut contours = if options.border_is_foreground { // A frame of foreground bits, its outline is not traced
+        let (rows, cols) = (bits.len(), bits[0].len());
+        super::grid::bordered_par(rows + 2, cols + 2, |r, c| r == 0 || c == 0 || r == rows + 1 || c == cols + 1 || bits[r - 1][c - 1] == 1)
+    } else {
+        bits_grid(bits)
+    };
+    let y_up = options.y_up_height(bits.len());
+    let mut framed: Vec<(i32, i32)> = Vec::new();
+    let mut traced: Vec<super::contour::Contour> = Vec::new(); // Only filled for the canonical order
+    let mut ids: HashMap<((i32, i32), bool), usize> = HashMap::new(); // The first vertex and the kind of a contour are unique
+    let mut id: usize = 0;
+    let mut normalized = super::contour::Contour::default();
+    let mut quantized: Vec<(i32, i32)> = Vec::new();
+    let mut flipped: Vec<(i32, i32)> = Vec::new();
+    let mut emit = |id: usize, is_hole: bool, points: &[(i32, i32)]| {
+        let points = if options.quantize > 1 {
+            quantize_points(points, is_hole, options.quantize as i32, &mut quantized);
+            &quantized[..]
+        } else {
+            points
+        };
+        let points = if options.normalize_start {
+            normalized.points.clear();
+            normalized.points.extend_from_slice(points);
+            normalized.normalize_start();
+            &normalized.points[..]
+        } else {
+            points
+        };
+        match y_up {
+            Some(height) => {
+                flipped.clear();
+                flipped.extend(points.iter().map(|p| (p.0, height - p.1)));
+                flipped[1..].reverse(); // Keep the direction of the outlines and the holes in the y-up space
+                f(id, is_hole, &flipped);
+            }
+            None => f(id, is_hole, points),
+        }
+    };
+    let (y0, y1) = options.row_range.as_ref().map_or((0, i64::MAX), |range| (range.start as i64, range.end as i64));
+    let max_depth = options.max_depth.map_or(usize::MAX, |depth| depth as usize);
+    let mut push = |outline: bool, depth: usize, points: &[(i32, i32)]| {
+        let points = if options.border_is_foreground {
+            if outline && points[0] == (0, 0) {
+                return true;
+            }
+            framed.clear();
+            framed.extend(points.iter().map(|p| (p.0 - 1, p.1 - 1)));
+            &framed[..]
+        } else {
+            points
+        };
+        let depth = if options.border_is_foreground { depth.saturating_sub(1) } else { depth }; // The frame is not a level
+        if points[0].1 as i64 >= y1 { // The next contours start below the rows too
+            return false;
+        }
+        // The row range and the area are only checked when they are set, so the default options cost no more than a plain trace
+        let in_rows = options.row_range.is_none() || points.iter().any(|p| p.1 as i64 > y0);
+        if depth <= max_depth && in_rows && (options.min_contour_area == 0 || super::contour::ring_signed_area(points).unsigned_abs() >= options.min_contour_area) {
+            if options.output_order == OutputOrder::Canonical {
+                ids.insert((points[0], !outline), id);
+                traced.push(super::contour::Contour::new(points.to_vec(), !outline));
+            } else {
+                emit(id, !outline, points);
+            }
+            id += 1;
+        }
+        true
+    };
+    match options.scan_order {
+        _ if options.connectivity == Connectivity::Eight => super::grid::scan_eight(&contours, &mut |outline, depth, points| { push(outline, depth, points); }),
+        ScanOrder::Morton(size) => super::grid::scan_morton(&contours, size, &mut |outline, depth, points| { push(outline, depth, points); }),
+        _ => {
+            let mut state = super::grid::ScanState::new();
+            let mut points: Vec<(i32, i32)> = Vec::new();
+            while let Some(outline) = state.next(&mut contours, &mut points) {
+                if !push(outline, state.depth(), &points) { break; }
+            }
+        }
+    }
+    if options.output_order == OutputOrder::Canonical {
+        let mut ordered: Vec<super::contour::Contour> = Vec::new();
+        canonical_order(super::contour::hierarchy(traced), &mut ordered);
+        for contour in &ordered {
+            emit(ids[&(contour.points[0], contour.is_hole)], contour.is_hole, &contour.points);
+        }
+    }
+}
+
+// Snap the vertices to the multiples of q and merge the collinear edges, a collapsed contour becomes the cells of the coarser grid covering it
+fn quantize_points(points: &[(i32, i32)], is_hole: bool, q: i32, out: &mut Vec<(i32, i32)>) {
+    out.clear();
+    out.extend(points.iter().map(|&(x, y)| ((x + q / 2).div_euclid(q) * q, (y + q / 2).div_euclid(q) * q)));
+    merge_collinear(out);
+    if out.len() < 3 {
+        let (min_x, min_y) = points.iter().fold((i32::MAX, i32::MAX), |m, p| (m.0.min(p.0), m.1.min(p.1)));
+        let (max_x, max_y) = points.iter().fold((i32::MIN, i32::MIN), |m, p| (m.0.max(p.0), m.1.max(p.1)));
+        let (x0, y0, x1, y1) = (min_x.div_euclid(q) * q, min_y.div_euclid(q) * q, (max_x + q - 1).div_euclid(q) * q, (max_y + q - 1).div_euclid(q) * q);
+        out.clear();
+        if is_hole {
+            out.extend_from_slice(&[(x0, y0), (x0, y1), (x1, y1), (x1, y0)]);
+        } else {
+            out.extend_from_slice(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1)]);
+        }
+    }
+}
+
+// Drop the duplicate vertices and the middle vertex of each collinear (or backtracking) triplet of a ring,
+// then start it again at its top left vertex (fewer than 3 vertices are left if the ring collapses)
+fn merge_collinear(points: &mut Vec<(i32, i32)>) {
+    let ring = std::mem::take(points);
+    for p in ring {
+        while points.len() >= 2 && {
+            let (a, b) = (points[points.len() - 2], points[points.len() - 1]);
+            b == p || (b.0 - a.0) * (p.1 - b.1) == (b.1 - a.1) * (p.0 - b.0)
+        } {
+            points.pop();
+        }
+        if points.last() != Some(&p) {
+            points.push(p);
+        }
+    }
+    // The same around the first vertex
+    loop {
+        let n = points.len();
+        if n >= 3 && (points[n - 1] == points[0] || (points[n - 1].0 - points[n - 2].0) * (points[0].1 - points[n - 1].1) == (points[n - 1].1 - points[n - 2].1) * (points[0].0 - points[n - 1].0)) {
+            points.pop();
+        } else if n >= 3 && (points[0].0 - points[n - 1].0) * (points[1].1 - points[0].1) == (points[0].1 - points[n - 1].1) * (points[1].0 - points[0].0) {
+            points.remove(0);
+        } else {
+            break;
+        }
+    }
+    if let Some(first) = (0..points.len()).min_by_key(|&i| (points[i].1, points[i].0)) {
+        points.rotate_left(first);
+    }
+}
+
+// Depth-first: an outline before its holes and a hole before the outlines inside it, the siblings sorted by top, left, then area
+fn canonical_order(mut nodes: Vec<super::contour::OutlineNode>, out: &mut Vec<super::contour::Contour>) {
+    sort_canonical(&mut nodes, |node| &node.outline);
+    for node in nodes {
+        out.push(node.outline);
+        let mut holes = node.holes;
+        sort_canonical(&mut holes, |hole| &hole.hole);
+        for hole in holes {
+            out.push(hole.hole);
+            canonical_order(hole.outlines, out);
+        }
+    }
+}
+
+fn sort_canonical<T, F: Fn(&T) -> &super::contour::Contour>(items: &mut [T], contour: F) {
+    items.sort_by_key(|item| {
+        let contour = contour(item);
+        let bbox = contour.bbox().unwrap_or_default();
+        (bbox.min_y, bbox.min_x, contour.signed_area().abs())
+    });
+}
+
+/// A function that takes a 2D array of bits and a vec of contours as input and overwrite the vec with the contours as output.
+///
+/// The content of the vec is **overwritten**: its length is set to the number of traced contours and the allocations
+/// of the existing contours (and of their points) are reused where possible, which is useful inside a hot loop.
+/// # Examples
+/// ```ignore
+/// use contour_tracing::{array::trace_into, contour::Contour};
+/// ```
+/// - Reuse the same vec of contours for every frame:
+///
+/// ```edition2018
+/// # use contour_tracing::{array::trace_into, contour::Contour};
+/// let mut contours: Vec<Contour> = Vec::new();
+///
+/// for bits in [vec![vec![ 1,1 ]], vec![vec![ 1,0,1 ]]] {
+///     trace_into(&bits, &mut contours);
+///     println!("{:?}", contours);
+/// }
+/// # assert_eq!(contours, vec![Contour::new(vec![(0, 0), (1, 0), (1, 1), (0, 1)], false), Contour::new(vec![(2, 0), (3, 0), (3, 1), (2, 1)], false)]);
+/// ```
+pub fn trace_into(bits: &[Vec<i8>], out: &mut Vec<super::contour::Contour>) {
+    let mut n: usize = 0;
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        super::contour::reuse_contour(out, n, points, !outline);
+        n += 1;
+    });
+    out.truncate(n);
+}
+
+/// A function that takes a 2D array of bits and an arena as input and return the contours, whose vertices are borrowed from the arena, as output.
+///
+/// The content of the arena is **overwritten** with the vertices of all the contours one after the other, and each
+/// [`ContourRef`](super::contour::ContourRef) borrows its slice of the arena, so there is no allocation for each contour
+/// (the arena only grows, and its allocation is reused from a trace to the next). The arena stays borrowed as long as
+/// the returned contours are alive: they must be dropped (or copied with [`ContourRef::to_contour`](super::contour::ContourRef::to_contour))
+/// before the arena is reused.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::{array::trace_into_arena, contour::ContourRef};
+/// let mut arena: Vec<(i32, i32)> = Vec::new();
+///
+/// for bits in [vec![vec![ 1,1 ]], vec![vec![ 1,0,1 ]]] {
+///     let contours: Vec<ContourRef> = trace_into_arena(&bits, &mut arena);
+///     println!("{:?}", contours);
+/// }
+/// # assert_eq!(arena.len(), 8);
+/// ```
+pub fn trace_into_arena<'a>(bits: &[Vec<i8>], arena: &'a mut Vec<(i32, i32)>) -> Vec<super::contour::ContourRef<'a>> {
+    arena.clear();
+    let mut ranges: Vec<(usize, usize, bool)> = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        ranges.push((arena.len(), arena.len() + points.len(), !outline));
+        arena.extend_from_slice(points);
+    });
+    let arena: &'a [(i32, i32)] = arena;
+    ranges.into_iter().map(|(start, end, is_hole)| super::contour::ContourRef { points: &arena[start..end], is_hole }).collect()
+}
+
+/// A visitor of the traced contours, see [`trace_visit`].
+pub trait ContourVisitor {
+    /// Called before the vertices of a contour, with `true` if the contour is a hole and `false` if it is an outline.
+    fn begin_contour(&mut self, is_hole: bool);
+    /// Called for each vertex of the contour, in the order of the contour (the last vertex is not repeated).
+    fn vertex(&mut self, x: i32, y: i32);
+    /// Called after the last vertex of the contour.
+    fn end_contour(&mut self);
+}
+
+/// A function that takes a 2D array of bits and a visitor as input and call the visitor for each contour and each of its vertices,
+/// in the order of [`bits_to_contours`].
+///
+/// No string nor vec of contours is built (the tracer only reuses a single scratch vec of vertices), so the contours can be
+/// streamed into any data structure, e.g. the SVG Path commands or the points of a polygon.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{trace_visit, ContourVisitor};
+/// struct Counter { outlines: usize, holes: usize, vertices: usize }
+///
+/// impl ContourVisitor for Counter {
+///     fn begin_contour(&mut self, is_hole: bool) {
+///         if is_hole { self.holes += 1 } else { self.outlines += 1 }
+///     }
+///     fn vertex(&mut self, _x: i32, _y: i32) {
+///         self.vertices += 1;
+///     }
+///     fn end_contour(&mut self) {}
+/// }
+///
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let mut counter = Counter { outlines: 0, holes: 0, vertices: 0 };
+/// trace_visit(&bits, &mut counter);
+/// # assert_eq!((counter.outlines, counter.holes, counter.vertices), (1, 1, 8));
+/// ```
+pub fn trace_visit<V: ContourVisitor>(bits: &[Vec<i8>], visitor: &mut V) {
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        visitor.begin_contour(!outline);
+        for &(x, y) in points {
+            visitor.vertex(x, y);
+        }
+        visitor.end_contour();
+    });
+}
+
+/// An iterator over the steps of the Theo Pavlidis' tracer along a single contour, as `(x, y, direction)`:
+/// the cell where the tracer stands and its orientation, after each move or turn.
+///
+/// The tracers are the ones of [`bits_to_paths`]: the tracer of an outline stands in the foreground cells along it and goes
+/// clockwise, the tracer of a hole stands in the background cells along it and goes counterclockwise, both with the foreground
+/// on the right-hand side of the edges. [`ContourWalk::new`] walks the contour on a side of a foreground cell, and
+/// [`ContourWalk::hole`] the hole on a side of a background cell. The walk starts at the first step of the turn where
+/// the tracer stands on the seed, and stops after one turn, when the tracer would be back at its first step. From the top
+/// left cell of a shape, the steps are the ones of the tracer of [`bits_to_paths`], starting facing [`Direction::East`].
+///
+/// The array of bits is borrowed and only read around the tracer: the first step is found by walking the contour a few times
+/// when the walk is created (in the time of its perimeter, without allocating), then each step is computed when it is needed.
+///
+/// A cell between holes touching by their corners is walked around as an outline, though [`bits_to_paths`] does not trace
+/// this outline (its edges are in the holes around it).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{ContourWalk, Direction};
+/// let bits = vec![vec![ 1,1 ]];
+///
+/// let steps: Vec<_> = ContourWalk::new(&bits, 0, 0).unwrap().collect();
+/// # assert_eq!(steps, vec![(0, 0, Direction::East), (1, 0, Direction::East), (1, 0, Direction::South), (1, 0, Direction::West), (0, 0, Direction::West), (0, 0, Direction::North)]);
+/// for (x, y, direction) in steps {
+///     println!("{} {} {:?}", x, y, direction);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ContourWalk<'a> {
+    bits: &'a [Vec<i8>],
+    outline: bool,
+    tracer: WalkState,
+    remaining: usize,
+}
+
+// The position of the tracer of a walk (with a border around the array of bits) and its orientation
+type WalkState = (usize, usize, [usize; 8]);
+
+impl<'a> ContourWalk<'a> {
+    /// Walk along the contour of the foreground cell at the column `x` and the row `y` of the array of bits, on its first side
+    /// in the background or outside the array (top, right, bottom, then left): the outline of the shape of the cell, or one of
+    /// its holes (the walk then starts in the background cell on this side).
+    ///
+    /// Return `None` if the seed is not in the array, not in the foreground, or inside its shape (its 4 neighbors are in the foreground).
+    pub fn new(bits: &'a [Vec<i8>], x: usize, y: usize) -> Option<Self> {
+        if bits.get(y).and_then(|row| row.get(x)) != Some(&1) {
+            return None;
+        }
+        let (x, y) = (x + 1, y + 1);
+        // Face East along a background cell on the top, South along one on the right, and so on (the background on the left)
+        let (side, direction) = *[(0, 2), (2, 4), (4, 6), (6, 0)].iter().find(|&&(side, _)| walk_neighbor(bits, x, y, side) < 1)?;
+        let (walk, area) = ContourWalk::start(bits, true, x, y, direction);
+        if area > 0 {
+            return Some(walk);
+        }
+        // The background side is in a hole, which the tracer of the holes walks from the background cell with the seed on its right
+        let (hole_x, hole_y) = (x.wrapping_add(super::MN[side].0 as usize), y.wrapping_add(super::MN[side].1 as usize));
+        Some(ContourWalk::start(bits, false, hole_x, hole_y, (side + 2) % 8).0)
+    }
+
+    /// Walk around the hole of the background cell at the column `x` and the row `y` of the array of bits, along its first side
+    /// in the foreground around the hole (top, right, bottom, then left).
+    ///
+    /// Return `None` if the seed is not in the array, in the foreground, or not next to the foreground around a hole
+    /// (e.g. a cell outside the shapes, or only next to an island inside the hole).
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::array::{ContourWalk, Direction};
+    /// let ring = vec![vec![ 1,1,1 ],
+    ///                 vec![ 1,0,1 ],
+    ///                 vec![ 1,1,1 ]];
+    ///
+    /// let steps: Vec<_> = ContourWalk::hole(&ring, 1, 1).unwrap().collect();
+    /// # assert_eq!(steps, vec![(1, 1, Direction::West), (1, 1, Direction::South), (1, 1, Direction::East), (1, 1, Direction::North)]);
+    /// println!("{:?}", steps);
+    /// ```
+    pub fn hole(bits: &'a [Vec<i8>], x: usize, y: usize) -> Option<Self> {
+        if bits.get(y).map_or(true, |row| row.get(x).map_or(true, |&bit| bit == 1)) {
+            return None;
+        }
+        let (x, y) = (x + 1, y + 1);
+        // Face West along a foreground cell on the top, North along one on the right, and so on (the foreground on the right),
+        // along the first side which is on a hole: the turn of the tracer of the outlines along this side is counterclockwise
+        let &(_, direction) = [(0, 6), (2, 0), (4, 2), (6, 4)].iter().find(|&&(side, _)| {
+            let (fg_x, fg_y) = (x.wrapping_add(super::MN[side].0 as usize), y.wrapping_add(super::MN[side].1 as usize));
+            walk_cell(bits, fg_x, fg_y) == 1 && ContourWalk::start(bits, true, fg_x, fg_y, (side + 6) % 8).1 < 0
+        })?;
+        Some(ContourWalk::start(bits, false, x, y, direction).0)
+    }
+
+    // Start a walk at (x, y) with a border facing a direction (an index of MN), and return it with the signed area of its turn
+    fn start(bits: &'a [Vec<i8>], outline: bool, x: usize, y: usize, direction: usize) -> (Self, i64) {
+        let mut o = [0usize; 8];
+        for (k, d) in o.iter_mut().enumerate() {
+            *d = (direction + k) % 8;
+        }
+        // The tracer may come to the boundary from the seed without coming back to it: find the turn with Brent's algorithm
+        let same = |a: &WalkState, b: &WalkState| (a.0, a.1, a.2[0]) == (b.0, b.1, b.2[0]);
+        let step = |state: WalkState| walk_step(bits, outline, state, &mut |_, _| {});
+        let (mut power, mut length) = (1usize, 1usize);
+        let mut tortoise = (x, y, o);
+        let mut hare = step(tortoise);
+        while !same(&tortoise, &hare) {
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+            hare = step(hare);
+            length += 1;
+        }
+        let (mut tortoise, mut hare) = ((x, y, o), (x, y, o));
+        for _ in 0..length {
+            hare = step(hare);
+        }
+        while !same(&tortoise, &hare) {
+            tortoise = step(tortoise);
+            hare = step(hare);
+        }
+        // Start the turn on the seed if the tracer stands on it, and sum the shoelace formula over the vertices of the turn
+        let (mut tracer, mut on_seed) = (tortoise, None);
+        let (mut area, mut first, mut last) = (0i64, None, (0i64, 0i64));
+        let mut vertex = |vx: i64, vy: i64| {
+            if first.is_none() {
+                first = Some((vx, vy));
+            } else {
+                area += last.0 * vy - vx * last.1;
+            }
+            last = (vx, vy);
+        };
+        for _ in 0..length {
+            if on_seed.is_none() && (tracer.0, tracer.1) == (x, y) {
+                on_seed = Some(tracer);
+            }
+            tracer = walk_step(bits, outline, tracer, &mut vertex);
+        }
+        if let Some((vx, vy)) = first {
+            area += last.0 * vy - vx * last.1;
+        }
+        (ContourWalk { bits, outline, tracer: on_seed.unwrap_or(tortoise), remaining: length }, area)
+    }
+}
+
+// The value of the neighbor of a cell at (x, y) with a border on a side (an index of MN), see walk_cell
+fn walk_neighbor(bits: &[Vec<i8>], x: usize, y: usize, side: usize) -> i8 {
+    walk_cell(bits, x.wrapping_add(super::MN[side].0 as usize), y.wrapping_add(super::MN[side].1 as usize))
+}
+
+// The value of a cell of the array of bits at (x, y) with a border, as in the grid of the tracer: 1 in the foreground, -1 in the background and 0 in the border
+fn walk_cell(bits: &[Vec<i8>], x: usize, y: usize) -> i8 {
+    match bits.get(y.wrapping_sub(1)).and_then(|row| row.get(x.wrapping_sub(1))) {
+        Some(&1) => 1,
+        Some(_) => -1,
+        None => 0,
+    }
+}
+
+// The next step of the tracer of an outline or a hole, reading the array of bits around it and calling vertex(x, y) for each vertex of the contour
+fn walk_step<V: FnMut(i64, i64)>(bits: &[Vec<i8>], outline: bool, (mut x, mut y, mut o): WalkState, vertex: &mut V) -> WalkState {
+    let mut neighbors = [0i8; 8];
+    for (side, neighbor) in neighbors.iter_mut().enumerate() {
+        *neighbor = walk_neighbor(bits, x, y, side);
+    }
+    let (rot, viv, offsets) = if outline { (2, (7, 1, 0), super::grid::O_VERTEX_WITH_BORDER) } else { (-2, (1, 7, 6), super::grid::H_VERTEX_WITH_BORDER) };
+    super::grid::trace_step(outline, &neighbors, &mut x, &mut y, &mut o, rot, viv, &mut |mark, vx, vy, d| {
+        if !mark {
+            vertex(vx as i64 + offsets[d].0 as i64, vy as i64 + offsets[d].1 as i64);
+        }
+    });
+    (x, y, o)
+}
+
+impl<'a> Iterator for ContourWalk<'a> {
+    type Item = (usize, usize, Direction);
+
+    fn next(&mut self) -> Option<(usize, usize, Direction)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (x, y, o) = self.tracer;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.tracer = walk_step(self.bits, self.outline, self.tracer, &mut |_, _| {});
+        }
+        let direction = match o[0] {
+            0 => Direction::North,
+            2 => Direction::East,
+            4 => Direction::South,
+            _ => Direction::West,
+        };
+        Some((x - 1, y - 1, direction))
+    }
+}
+
+/// A tracer which keeps its bordered buffer between the calls, e.g. to trace the same-sized frames of a video without
+/// allocating the buffer for each frame.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::BitTracer;
+/// let mut tracer = BitTracer::new(3, 1);
+///
+/// for bits in [vec![vec![ 1,1,0 ]], vec![vec![ 1,0,1 ]]] {
+///     println!("{}", tracer.trace(&bits, true));
+/// }
+/// # assert_eq!(tracer.trace(&[vec![ 1,0,1 ]], true), "M0 0H1V1H0ZM2 0H3V1H2Z");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitTracer {
+    contours: Vec<Vec<i8>>,
+}
+
+impl BitTracer {
+    /// Create a tracer for the 2D arrays of bits of `width` columns and `height` rows.
+    pub fn new(width: usize, height: usize) -> Self {
+        BitTracer { contours: vec![vec![0i8; width + 2]; height + 2] }
+    }
+
+    /// Same as [`bits_to_paths_ref`] but the buffer of the tracer is reused: it is only reallocated if the dimensions
+    /// of the 2D array of bits differ from the ones of the tracer.
+    pub fn trace(&mut self, bits: &[Vec<i8>], closepaths: bool) -> String {
+        let (rows, cols) = (bits.len(), bits.first().map_or(0, |row| row.len()));
+        self.contours.resize_with(rows + 2, Vec::new);
+        for (r, row) in self.contours.iter_mut().enumerate() {
+            row.clear();
+            row.resize(cols + 2, 0);
+            if r > 0 && r <= rows {
+                for (cell, &bit) in row[1..=cols].iter_mut().zip(&bits[r - 1]) {
+                    *cell = if bit == 1 { 1 } else { -1 };
+                }
+            }
+        }
+        let mut paths = String::new();
+        super::grid::scan(&mut self.contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+        paths
+    }
+}
+
+/// A function that takes a 2D array of bits and an epsilon as input and return, for each contour,
+/// the exact contour and the contour simplified with [`Contour::simplify`](super::contour::Contour::simplify) as output.
+///
+/// The bits are traced only once, the simplified contours are computed from the exact ones.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::{array::bits_to_contours_and_simplified, contour::Contour};
+/// let bits = vec![vec![ 1,0,0 ],
+///                 vec![ 1,1,0 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let pairs = bits_to_contours_and_simplified(&bits, 1.0);
+/// # assert_eq!(pairs[0].0.points, vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (3, 2), (3, 3), (0, 3)]);
+/// # assert_eq!(pairs[0].1.points, vec![(0, 0), (3, 3), (0, 3)]);
+/// for (exact, simplified) in pairs {
+///     println!("{:?} {:?}", exact.points, simplified.points);
+/// }
+/// ```
+pub fn bits_to_contours_and_simplified(bits: &[Vec<i8>], epsilon: f64) -> Vec<(super::contour::Contour, super::contour::Contour)> {
+    let mut pairs = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        let exact = super::contour::Contour::new(points.to_vec(), !outline);
+        let simplified = exact.simplify(epsilon);
+        pairs.push((exact, simplified));
+    });
+    pairs
+}
+
+/// A function that takes a 2D array of bits, an option and an epsilon as input and return a string of SVG Path commands
+/// with the contours simplified by [`Contour::simplify`](super::contour::Contour::simplify) (Ramer–Douglas–Peucker) as output.
+///
+/// The vertices within `epsilon` pixels of a simplified segment are removed, so the simplified segments are not always axis-aligned:
+/// they are given with SVG Path **L** commands, while the axis-aligned segments keep the **H** and **V** commands.
+/// With an epsilon of **0**, no vertex is removed and the paths are the ones of [`bits_to_paths`], byte for byte.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_simplified;
+/// let bits = vec![vec![ 1,0,0 ],
+///                 vec![ 1,1,0 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_paths_simplified(&bits, true, 1.0), "M0 0L3 3H0Z");
+/// # assert_eq!(bits_to_paths_simplified(&bits, true, 0.0), "M0 0H1V1H2V2H3V3H0Z");
+/// println!("{}", bits_to_paths_simplified(&bits, true, 1.0));
+/// ```
+pub fn bits_to_paths_simplified(bits: &[Vec<i8>], closepaths: bool, epsilon: f64) -> String {
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        let simplified = super::contour::Contour::new(points.to_vec(), !outline).simplify(epsilon);
+        let mut previous = simplified.points[0];
+        paths.push_str(&format!("M{} {}", previous.0, previous.1));
+        for &point in &simplified.points[1..] {
+            if point.1 == previous.1 { paths.push_str(&format!("H{}", point.0)); }
+            else if point.0 == previous.0 { paths.push_str(&format!("V{}", point.1)); }
+            else { paths.push_str(&format!("L{} {}", point.0, point.1)); }
+            previous = point;
+        }
+        if closepaths { paths.push('Z'); }
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits, an option, a number of iterations and a precision as input and return a string of SVG Path commands
+/// with the contours smoothed by the Chaikin's corner cutting algorithm as output.
+///
+/// Each iteration replaces each edge of a contour by two points at a quarter and three quarters of it, around the whole
+/// contour (the edge from the last vertex to the first one included), so the contours stay closed rings and keep their
+/// direction. The smoothed contours are given with SVG Path **L** commands, and each iteration doubles their number of vertices.
+/// The coordinates are rounded to `precision` decimals without the trailing zeros.
+/// With **0** iterations, the paths are the ones of [`bits_to_paths`], byte for byte.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_smoothed;
+/// let bits = vec![vec![ 1,1 ],
+///                 vec![ 1,1 ]];
+///
+/// # assert_eq!(bits_to_paths_smoothed(&bits, true, 1, 6), "M0.5 0L1.5 0L2 0.5L2 1.5L1.5 2L0.5 2L0 1.5L0 0.5Z");
+/// # assert_eq!(bits_to_paths_smoothed(&bits, true, 0, 6), "M0 0H2V2H0Z");
+/// println!("{}", bits_to_paths_smoothed(&bits, true, 2, 3));
+/// ```
+pub fn bits_to_paths_smoothed(bits: &[Vec<i8>], closepaths: bool, iterations: u32, precision: u8) -> String {
+    let mut paths = String::new();
+    let mut smoothed: Vec<(f64, f64)> = Vec::new();
+    let mut cut: Vec<(f64, f64)> = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        if iterations == 0 {
+            super::contour::push_path(&mut paths, points, closepaths);
+            return;
+        }
+        smoothed.clear();
+        smoothed.extend(points.iter().map(|p| (p.0 as f64, p.1 as f64)));
+        for _ in 0..iterations {
+            cut.clear();
+            for (i, &a) in smoothed.iter().enumerate() {
+                let b = smoothed[(i + 1) % smoothed.len()];
+                cut.push((0.75 * a.0 + 0.25 * b.0, 0.75 * a.1 + 0.25 * b.1));
+                cut.push((0.25 * a.0 + 0.75 * b.0, 0.25 * a.1 + 0.75 * b.1));
+            }
+            std::mem::swap(&mut smoothed, &mut cut);
+        }
+        paths.push_str(&format!("M{} {}", number(smoothed[0].0, precision), number(smoothed[0].1, precision)));
+        for p in &smoothed[1..] {
+            paths.push_str(&format!("L{} {}", number(p.0, precision), number(p.1, precision)));
+        }
+        if closepaths { paths.push('Z'); }
+    });
+    paths
+}
+
+/// Same as [`bits_to_paths_ref`] but every coordinate is multiplied by `scale`, e.g. to draw the pixels on a larger SVG canvas
+/// without a `transform` attribute.
+///
+/// The coordinates are rounded to `precision` decimals without the trailing zeros, so an integer scale gives integer coordinates
+/// (with a scale of **1**, the paths are the ones of [`bits_to_paths`], byte for byte).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_scaled;
+/// let bits = vec![vec![ 0,1 ]];
+///
+/// # assert_eq!(bits_to_paths_scaled(&bits, true, 2.0, 6), "M2 0H4V2H2Z");
+/// # assert_eq!(bits_to_paths_scaled(&bits, true, 0.25, 6), "M0.25 0H0.5V0.25H0.25Z");
+/// # assert_eq!(bits_to_paths_scaled(&bits, true, 0.25, 1), "M0.2 0H0.5V0.2H0.2Z");
+/// println!("{}", bits_to_paths_scaled(&bits, true, 2.5, 6));
+/// ```
+pub fn bits_to_paths_scaled(bits: &[Vec<i8>], closepaths: bool, scale: f64, precision: u8) -> String {
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        let mut previous = points[0];
+        paths.push_str(&format!("M{} {}", scaled(previous.0, scale, precision), scaled(previous.1, scale, precision)));
+        for &point in &points[1..] {
+            if point.1 == previous.1 { paths.push_str(&format!("H{}", scaled(point.0, scale, precision))); } else { paths.push_str(&format!("V{}", scaled(point.1, scale, precision))); }
+            previous = point;
+        }
+        if closepaths { paths.push('Z'); }
+    });
+    paths
+}
+
+/// Same as [`bits_to_paths_ref`] but every vertex is transformed by the affine matrix `[a, b, c, d, e, f]` (the order of the SVG
+/// `matrix` transform), i.e. `(x, y)` is emitted as `(a x + c y + e, b x + d y + f)`, e.g. to bake the position of a sprite in an atlas.
+///
+/// Without rotation or shear (`b` and `c` are **0**, e.g. a translation), the edges stay horizontal or vertical and the paths use
+/// SVG Path **H** and **V** commands, otherwise **L** commands. The coordinates are rounded to `precision` decimals
+/// without the trailing zeros.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_transformed;
+/// let bits = vec![vec![ 1,1 ]];
+///
+/// // A translation, then a rotation of 90 degrees
+/// # assert_eq!(bits_to_paths_transformed(&bits, true, [1.0, 0.0, 0.0, 1.0, 10.0, 20.0], 6), "M10 20H12V21H10Z");
+/// # assert_eq!(bits_to_paths_transformed(&bits, true, [0.0, 1.0, -1.0, 0.0, 0.0, 0.0], 6), "M0 0L0 2L-1 2L-1 0Z");
+/// println!("{}", bits_to_paths_transformed(&bits, true, [0.0, 1.0, -1.0, 0.0, 0.0, 0.0], 6));
+/// ```
+pub fn bits_to_paths_transformed(bits: &[Vec<i8>], closepaths: bool, transform: [f32; 6], precision: u8) -> String {
+    let [a, b, c, d, e, f] = transform;
+    let (a, b, c, d, e, f) = (a as f64, b as f64, c as f64, d as f64, e as f64, f as f64);
+    let apply = |p: (i32, i32)| (a * p.0 as f64 + c * p.1 as f64 + e, b * p.0 as f64 + d * p.1 as f64 + f);
+    let axis_aligned = b == 0.0 && c == 0.0;
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        let first = apply(points[0]);
+        paths.push_str(&format!("M{} {}", number(first.0, precision), number(first.1, precision)));
+        for i in 1..points.len() {
+            let p = apply(points[i]);
+            if !axis_aligned {
+                paths.push_str(&format!("L{} {}", number(p.0, precision), number(p.1, precision)));
+            } else if points[i].1 == points[i - 1].1 {
+                paths.push_str(&format!("H{}", number(p.0, precision)));
+            } else {
+                paths.push_str(&format!("V{}", number(p.1, precision)));
+            }
+        }
+        if closepaths { paths.push('Z'); }
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits, an offset and an option as input and return,
+/// for each contour, a fill path and an offset stroke path (strings of SVG Path commands) as output.
+///
+/// The outlines are grown by `delta` pixels (shrunk if `delta` is negative) and the holes are shrunk by `delta` pixels,
+/// so the stroke path surrounds the fill path like the border of a sticker. The convex and concave corners are offset
+/// with mitered (square) corners, and an offset contour can split into several contours or vanish.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_fill_and_offset_paths;
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_fill_and_offset_paths(&bits, 1, true), vec![("M0 0H3V3H0Z".to_string(), "M-1 -1H4V4H-1Z".to_string()), ("M1 1V2H2V1Z".to_string(), "".to_string())]);
+/// for (fill, stroke) in bits_to_fill_and_offset_paths(&bits, 1, true) {
+///     println!("<path d=\"{}\"/><path d=\"{}\" fill=\"none\" stroke=\"black\"/>", fill, stroke);
+/// }
+/// ```
+pub fn bits_to_fill_and_offset_paths(bits: &[Vec<i8>], delta: i32, closepaths: bool) -> Vec<(String, String)> {
+    let mut paths = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        let mut fill = String::new();
+        super::contour::push_path(&mut fill, points, closepaths);
+        let mut stroke = String::new();
+        for contour in super::contour::offset_ring(points, !outline, if outline { delta } else { -delta }) {
+            super::contour::push_path(&mut stroke, &contour.points, closepaths);
+        }
+        paths.push((fill, stroke));
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits, an option, an offset and a precision as input and return a string of SVG Path commands
+/// with the contours offset by `offset` pixels as output.
+///
+/// Each edge is moved by `offset` pixels away from the foreground (towards it if `offset` is negative), and each vertex
+/// is the mitered join of its two edges, so the corners stay right angles: the outlines are grown and their holes shrunk
+/// by a positive offset. The offset can be fractional (the coordinates are rounded to `precision` decimals without the trailing zeros),
+/// and with an offset of **0** the paths are the ones of [`bits_to_paths`].
+/// Unlike [`bits_to_fill_and_offset_paths`], the offset contours are not traced again: a contour whose offset collapses
+/// or inverts (an edge becomes empty or reversed, e.g. a hole which is not larger than twice the offset) is dropped,
+/// and an offset larger than half of a concave part of a contour makes it overlap itself.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_paths_offset;
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_paths_offset(&bits, true, 0.25, 6), "M-0.25 -0.25H3.25V3.25H-0.25ZM1.25 1.25V1.75H1.75V1.25Z");
+/// println!("{}", bits_to_paths_offset(&bits, true, 0.25, 2));
+/// ```
+pub fn bits_to_paths_offset(bits: &[Vec<i8>], closepaths: bool, offset: f64, precision: u8) -> String {
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        let n = points.len();
+        // The foreground is on the right-hand side of each edge, so (dy, -dx) points away from it
+        let normal = |i: usize| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            (((b.1 - a.1).signum()) as f64, (-(b.0 - a.0).signum()) as f64)
+        };
+        let vertex = |i: usize| {
+            let (incoming, outgoing) = (normal((i + n - 1) % n), normal(i));
+            (points[i].0 as f64 + offset * (incoming.0 + outgoing.0), points[i].1 as f64 + offset * (incoming.1 + outgoing.1))
+        };
+        let vertices: Vec<(f64, f64)> = (0..n).map(vertex).collect();
+        // A contour collapses or inverts where one of its edges would be empty or reversed
+        let inverted = (0..n).any(|i| {
+            let (a, b, j) = (points[i], points[(i + 1) % n], (i + 1) % n);
+            ((b.0 - a.0) as f64) * (vertices[j].0 - vertices[i].0) + ((b.1 - a.1) as f64) * (vertices[j].1 - vertices[i].1) <= 0.0
+        });
+        if inverted {
+            return;
+        }
+        paths.push_str(&format!("M{} {}", number(vertices[0].0, precision), number(vertices[0].1, precision)));
+        for i in 1..n {
+            let p = vertices[i];
+            if points[i].1 == points[i - 1].1 { paths.push_str(&format!("H{}", number(p.0, precision))); } else { paths.push_str(&format!("V{}", number(p.1, precision))); }
+        }
+        if closepaths { paths.push('Z'); }
+    });
+    paths
+}
+
+/// A function that takes a width, a height, a closure and an option as input and return a string of SVG Path commands as output.
+///
+/// The closure is called once for each cell `(x, y)` of the `width` x `height` grid and returns `true` for the foreground,
+/// its results are cached internally so no 2D array of bits is needed, e.g. to trace analytic or procedural shapes.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::trace_fn;
+/// // A disc with a radius of 3
+/// let disc = trace_fn(7, 7, |x, y| (x - 3) * (x - 3) + (y - 3) * (y - 3) <= 9, true);
+/// # assert_eq!(disc, "M3 0H4V1H6V3H7V4H6V6H4V7H3V6H1V4H0V3H1V1H3Z");
+/// println!("{}", disc);
+/// ```
+pub fn trace_fn<F: Fn(i32, i32) -> bool>(width: usize, height: usize, f: F, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered(height, width, |r, c| f(c as i32, r as i32));
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes two 2D arrays of bits (before and after) and an option as input and return two strings of SVG Path commands,
+/// the added regions and the removed regions, as output.
+///
+/// The added regions are the bits which are foreground in `b` but not in `a`, the removed regions are the bits which are foreground
+/// in `a` but not in `b`, and each set difference is traced separately, e.g. to style them differently on a change detection dashboard.
+/// The arrays must have the same dimensions, otherwise a [`DimensionMismatch`] error is returned.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::diff_paths;
+/// let a = vec![vec![ 1,1,0 ]];
+/// let b = vec![vec![ 0,1,1 ]];
+///
+/// let (added, removed) = diff_paths(&a, &b, true).unwrap();
+/// # assert_eq!(added, "M2 0H3V1H2Z");
+/// # assert_eq!(removed, "M0 0H1V1H0Z");
+/// println!("{} {}", added, removed);
+/// ```
+pub fn diff_paths(a: &[Vec<i8>], b: &[Vec<i8>], closepaths: bool) -> Result<(String, String), DimensionMismatch> {
+    let (rows, cols) = same_dimensions(a, b)?;
+    let trace = |from: &[Vec<i8>], to: &[Vec<i8>]| {
+        let mut contours = super::grid::bordered_par(rows, cols, |r, c| to[r][c] == 1 && from[r][c] != 1);
+        let mut paths = String::new();
+        super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+        paths
+    };
+    Ok((trace(a, b), trace(b, a)))
+}
+
+/// A function that takes a 2D array of bits, a 2D array of booleans (the region of interest) and an option as input and
+/// return a string of SVG Path commands as output.
+///
+/// A bit is in the foreground when it is a foreground bit and it is in the region of interest (`true`), so the contours
+/// are the ones of the intersection, without computing it first. The arrays must have the same dimensions, otherwise
+/// a [`DimensionMismatch`] error is returned.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::masked_trace;
+/// let bits = vec![vec![ 1,1,1 ]];
+/// let roi = vec![vec![ false,true,true ]];
+///
+/// # assert_eq!(masked_trace(&bits, &roi, true), Ok("M1 0H3V1H1Z".to_string()));
+/// println!("{:?}", masked_trace(&bits, &roi, true));
+/// ```
+pub fn masked_trace(bits: &[Vec<i8>], roi: &[Vec<bool>], closepaths: bool) -> Result<String, DimensionMismatch> {
+    let (rows, cols) = same_dimensions(bits, roi)?;
+    let mut contours = super::grid::bordered_par(rows, cols, |r, c| bits[r][c] == 1 && roi[r][c]);
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    Ok(paths)
+}
+
+// The number of rows and columns of two 2D arrays, which must have the same dimensions
+fn same_dimensions<T, U>(a: &[Vec<T>], b: &[Vec<U>]) -> Result<(usize, usize), DimensionMismatch> {
+    let (size_a, size_b) = ((a.len(), a.first().map_or(0, |row| row.len())), (b.len(), b.first().map_or(0, |row| row.len())));
+    if a.len() != b.len() || a.iter().zip(b.iter()).any(|(ra, rb)| ra.len() != rb.len()) {
+        return Err(DimensionMismatch { a: size_a, b: size_b });
+    }
+    Ok(size_a)
+}
+
+/// A function that takes two 2D arrays of bits as input and return whether they produce the same contours as output.
+///
+/// The contours are determined by the foreground bits only (and the foreground bits by the contours), so the arrays are compared
+/// bit by bit without tracing them; the comparison stops at the first foreground bit which differs. The values other than **1**
+/// are all background and the missing bits (e.g. arrays with different dimensions) too, so the order and the start vertices
+/// of the contours never matter.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::contours_equal;
+/// let a = vec![vec![ 1,1,0 ]];
+/// let b = vec![vec![ 1,1 ],
+///              vec![ 0,0 ]];
+///
+/// assert!(contours_equal(&a, &b));
+/// assert!(!contours_equal(&a, &[vec![ 1,0,1 ]]));
+/// ```
+pub fn contours_equal(a: &[Vec<i8>], b: &[Vec<i8>]) -> bool {
+    let empty: Vec<i8> = Vec::new();
+    (0..a.len().max(b.len())).all(|r| {
+        let (ra, rb) = (a.get(r).unwrap_or(&empty), b.get(r).unwrap_or(&empty));
+        (0..ra.len().max(rb.len())).all(|c| (ra.get(c) == Some(&1)) == (rb.get(c) == Some(&1)))
+    })
+}
+
+/// A function that takes a 2D array of bits, a contour filter and an option as input and return a string of SVG Path commands
+/// of the kinds of contours given by the filter as output.
+///
+/// All the contours are still traced, so the skipped ones are not traced again as other contours: the paths are the ones
+/// of [`bits_to_paths`] without the skipped contours, in the same order.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_filtered, ContourFilter};
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_paths_filtered(&bits, ContourFilter::All, true), "M0 0H3V3H0ZM1 1V2H2V1Z");
+/// # assert_eq!(bits_to_paths_filtered(&bits, ContourFilter::HolesOnly, true), "M1 1V2H2V1Z");
+/// println!("{}", bits_to_paths_filtered(&bits, ContourFilter::OutlinesOnly, true));
+/// ```
+pub fn bits_to_paths_filtered(bits: &[Vec<i8>], filter: ContourFilter, closepaths: bool) -> String {
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| {
+        if filter == ContourFilter::All || outline == (filter == ContourFilter::OutlinesOnly) {
+            super::contour::push_path(&mut paths, points, closepaths);
+        }
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits, a fill rule and an option as input and return a string of SVG Path commands as output,
+/// rendered correctly under the fill rule when all the paths are in the same `d` attribute.
+///
+/// Under `evenodd`, only the nesting of the contours matters, so the paths are the ones of [`bits_to_paths`].
+/// Under `nonzero`, each hole must be wound opposite to the outline around it, and each island opposite to its hole:
+/// the traced contours are checked with [`validate`](super::contour::validate) (clockwise outlines and counterclockwise holes),
+/// and the index of the first contour wound the wrong way is returned as an error, which means a bug of the tracer.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_for_fill_rule, FillRule};
+/// // An island in a hole
+/// let bits = vec![vec![ 1,1,1,1,1 ],
+///                 vec![ 1,0,0,0,1 ],
+///                 vec![ 1,0,1,0,1 ],
+///                 vec![ 1,0,0,0,1 ],
+///                 vec![ 1,1,1,1,1 ]];
+///
+/// let rule = FillRule::NonZero;
+/// let d = bits_to_paths_for_fill_rule(&bits, rule, true).expect("a contour is wound the wrong way");
+/// # assert_eq!(d, "M0 0H5V5H0ZM1 1V4H4V1ZM2 2H3V3H2Z");
+/// println!(r#"<path d="{}" fill-rule="{}"/>"#, d, rule.as_str());
+/// ```
+pub fn bits_to_paths_for_fill_rule(bits: &[Vec<i8>], fill_rule: FillRule, closepaths: bool) -> Result<String, usize> {
+    match fill_rule {
+        FillRule::EvenOdd => Ok(bits_to_paths_ref(bits, closepaths)),
+        FillRule::NonZero => {
+            let contours = bits_to_contours(bits);
+            super::contour::validate(&contours)?;
+            let mut paths = String::new();
+            for contour in &contours {
+                super::contour::push_path(&mut paths, &contour.points, closepaths);
+            }
+            Ok(paths)
+        }
+    }
+}
+
+/// A function that takes a 2D array of bits of a glyph, a font winding convention and an option as input and return a string of SVG Path commands as output.
+///
+/// The coordinates are in the glyph space: the y axis goes up and the origin is the bottom left corner of the array of bits,
+/// i.e. `(x, y)` is emitted as `(x, rows - y)`. In this space, the outer contours and the counters (the holes of glyphs like
+/// **o** or **a**) are wound as required by the [`FontWinding`], so the font rasterizer fills the glyph correctly.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_glyph_paths, FontWinding};
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_glyph_paths(&bits, FontWinding::TrueType, true), "M0 3H3V0H0ZM1 2V1H2V2Z");
+/// # assert_eq!(bits_to_glyph_paths(&bits, FontWinding::PostScript, true), "M0 3V0H3V3ZM1 2H2V1H1Z");
+/// println!("{}", bits_to_glyph_paths(&bits, FontWinding::TrueType, true));
+/// ```
+pub fn bits_to_glyph_paths(bits: &[Vec<i8>], winding: FontWinding, closepaths: bool) -> String {
+    let rows = bits.len() as i32;
+    let mut paths = String::new();
+    super::grid::scan(&mut bits_grid(bits), |_, points| {
+        let mut glyph: Vec<(i32, i32)> = points.iter().map(|p| (p.0, rows - p.1)).collect(); // Flipping the y axis reverses the direction
+        if winding == FontWinding::PostScript {
+            glyph[1..].reverse();
+        }
+        super::contour::push_path(&mut paths, &glyph, closepaths);
+    });
+    paths
+}
+
+/// A function that takes a 2D array of bits and an option as input and return the contours and their hierarchy
+/// in the layout of the OpenCV `findContours` function as output.
+///
+/// The layout is the one of `findContours` with `RETR_TREE`:
+/// - each contour is a vec of pixels (not of pixel corners): the foreground pixels along the contour, without repetition
+///   of consecutive pixels, e.g. a single pixel is a contour of one point
+/// - an outline is counterclockwise in the image coordinates (y down) and starts at its top left pixel, a hole is
+///   clockwise and starts at the foreground pixel on the left of its top left background pixel, like `findContours`
+/// - with the **simple option** set to **true**, only the end pixels of the horizontal, vertical and diagonal segments
+///   are kept (`CHAIN_APPROX_SIMPLE`), otherwise all the pixels are kept (`CHAIN_APPROX_NONE`)
+/// - the hierarchy has one `[next, previous, first_child, parent]` entry for each contour, with the indexes of
+///   the next and previous contours at the same level (with the same parent), of the first child (a hole of an outline,
+///   or an outline in a hole) and of the parent, or **-1** if there is none
+///
+/// The contours are in scan order, which is also the order in which `findContours` finds them (the order of its output
+/// is different, so the contours should be matched by their first pixels). With `RETR_CCOMP`, `findContours` returns only
+/// two levels: the same contours, but the outlines in holes are top-level contours (their parents are **-1**).
+/// Unlike `findContours` which considers the foreground 8-connected, the foreground is 4-connected here, so the contours
+/// are the same unless some foreground pixels touch only by a corner.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_opencv_contours;
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let (contours, hierarchy) = bits_to_opencv_contours(&bits, true);
+/// # assert_eq!(contours, vec![vec![(0, 0), (0, 2), (2, 2), (2, 0)], vec![(0, 1), (1, 0), (2, 1), (1, 2)]]);
+/// # assert_eq!(hierarchy, vec![[-1, -1, 1, -1], [-1, -1, -1, 0]]);
+/// println!("{:?} {:?}", contours, hierarchy);
+/// ```
+pub fn bits_to_opencv_contours(bits: &[Vec<i8>], simple: bool) -> OpenCvContours {
+    let mut traced: Vec<super::contour::Contour> = Vec::new();
+    super::grid::scan(&mut bits_grid(bits), |outline, points| traced.push(super::contour::Contour::new(points.to_vec(), !outline)));
+    let contours: Vec<Vec<(i32, i32)>> = traced.iter().map(|contour| opencv_pixels(contour, simple)).collect();
+    let parents = super::contour::parents(&traced);
+    let mut hierarchy: Vec<[i32; 4]> = vec![[-1, -1, -1, -1]; traced.len()];
+    let mut last_child: HashMap<Option<usize>, usize> = HashMap::new();
+    for (i, &parent) in parents.iter().enumerate() {
+        hierarchy[i][3] = parent.map_or(-1, |p| p as i32);
+        match last_child.insert(parent, i) {
+            Some(previous) => {
+                hierarchy[previous][0] = i as i32;
+                hierarchy[i][1] = previous as i32;
+            }
+            None => if let Some(p) = parent { hierarchy[p][2] = i as i32 },
+        }
+    }
+    (contours, hierarchy)
+}
+
+// The foreground pixels along a contour (on the right-hand side of its edges), in the direction and from the start of OpenCV
+fn opencv_pixels(contour: &super::contour::Contour, simple: bool) -> Vec<(i32, i32)> {
+    let points = &contour.points;
+    let mut pixels: Vec<(i32, i32)> = Vec::new();
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let step = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+        let mut p = a;
+        while p != b {
+            let pixel = match step { (1, _) => (p.0, p.1), (-1, _) => (p.0 - 1, p.1 - 1), (_, 1) => (p.0 - 1, p.1), _ => (p.0, p.1 - 1) };
+            if pixels.last() != Some(&pixel) {
+                pixels.push(pixel);
+            }
+            p = (p.0 + step.0, p.1 + step.1);
+        }
+    }
+    while pixels.len() > 1 && pixels.first() == pixels.last() {
+        pixels.pop();
+    }
+    pixels.reverse();
+    let start = if contour.is_hole { (points[0].0 - 1, points[0].1) } else { points[0] };
+    if let Some(first) = pixels.iter().position(|&p| p == start) {
+        pixels.rotate_left(first);
+    }
+    if simple && pixels.len() > 2 {
+        let n = pixels.len();
+        let direction = |a: (i32, i32), b: (i32, i32)| (b.0 - a.0, b.1 - a.1);
+        let kept: Vec<(i32, i32)> = (0..n)
+            .filter(|&i| i == 0 || direction(pixels[(i + n - 1) % n], pixels[i]) != direction(pixels[i], pixels[(i + 1) % n]))
+            .map(|i| pixels[i])
+            .collect();
+        pixels = kept;
+    }
+    pixels
+}
+
+/// A function that takes a 2D array of bits as input and return axis-aligned rectangles `(x, y, width, height)`
+/// which cover exactly the foreground as output.
+///
+/// Each row is split into horizontal runs of foreground bits, and the runs with the same columns in consecutive rows
+/// are merged into a single rectangle (greedily, from top to bottom). The rectangles do not overlap and their union is
+/// the foreground, but they are not the minimal cover nor the only one. They are sorted by top, then left.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_rectangles;
+/// let bits = vec![vec![ 1,1,0 ],
+///                 vec![ 1,1,0 ],
+///                 vec![ 1,1,1 ]];
+///
+/// # assert_eq!(bits_to_rectangles(&bits), vec![(0, 0, 2, 2), (0, 2, 3, 1)]);
+/// println!("{:?}", bits_to_rectangles(&bits));
+/// ```
+pub fn bits_to_rectangles(bits: &[Vec<i8>]) -> Vec<(i32, i32, i32, i32)> {
+    let mut rectangles: Vec<(i32, i32, i32, i32)> = Vec::new();
+    let mut open: HashMap<(i32, i32), usize> = HashMap::new(); // The rectangles which end on the previous row, by columns
+    for (y, row) in bits.iter().enumerate() {
+        let mut next: HashMap<(i32, i32), usize> = HashMap::new();
+        let mut x = 0;
+        while x < row.len() {
+            if row[x] != 1 {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < row.len() && row[x] == 1 {
+                x += 1;
+            }
+            let run = (start as i32, x as i32);
+            let index = match open.remove(&run) {
+                Some(index) => {
+                    rectangles[index].3 += 1;
+                    index
+                }
+                None => {
+                    rectangles.push((run.0, y as i32, run.1 - run.0, 1));
+                    rectangles.len() - 1
+                }
+            };
+            next.insert(run, index);
+        }
+        open = next;
+    }
+    rectangles.sort_by_key(|r| (r.1, r.0));
+    rectangles
+}
+
+/// A function that takes a 2D array of bits as input and return the bounding box of the foreground (or `None` if there
+/// is no foreground) as output.
+///
+/// The bits are scanned only once and nothing is traced, so it is a cheap way to skip the empty arrays of bits.
+/// The bounding box is in the coordinates of the contours: it is the bounding box of all the traced outlines.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::{array::foreground_bbox, contour::BBox};
+/// let bits = vec![vec![ 0,0,0,0 ],
+///                 vec![ 0,1,0,0 ],
+///                 vec![ 0,0,1,0 ]];
+///
+/// # assert_eq!(foreground_bbox(&bits), Some(BBox { min_x: 1, min_y: 1, max_x: 3, max_y: 3 }));
+/// # assert_eq!(foreground_bbox(&[vec![ 0,0 ]]), None);
+/// if let Some(bbox) = foreground_bbox(&bits) {
+///     println!("{:?}", bbox);
+/// }
+/// ```
+pub fn foreground_bbox(bits: &[Vec<i8>]) -> Option<super::contour::BBox> {
+    let mut bbox: Option<super::contour::BBox> = None;
+    for (y, row) in bits.iter().enumerate() {
+        let (first, last) = match (row.iter().position(|&b| b == 1), row.iter().rposition(|&b| b == 1)) {
+            (Some(first), Some(last)) => (first as i32, last as i32 + 1),
+            _ => continue,
+        };
+        let y = y as i32;
+        bbox = Some(match bbox {
+            Some(b) => super::contour::BBox { min_x: b.min_x.min(first), min_y: b.min_y, max_x: b.max_x.max(last), max_y: y + 1 },
+            None => super::contour::BBox { min_x: first, min_y: y, max_x: last, max_y: y + 1 },
+        });
+    }
+    bbox
+}
+
+/// A function that takes a 2D array of bits and an option as input and return the SVG Path commands of the first contour
+/// (or `None` if there is no foreground) as output.
+///
+/// The scan stops as soon as the first contour is traced, so it is faster than [`bits_to_paths`] to check if there is an object.
+/// The first contour is the first path of [`bits_to_paths`]: the outline of the topmost (then leftmost) foreground bit.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::first_contour_path;
+/// let bits = vec![vec![ 0,0,0,0 ],
+///                 vec![ 0,0,1,1 ],
+///                 vec![ 1,0,1,1 ]];
+///
+/// # assert_eq!(first_contour_path(&bits, true), Some("M2 1H4V3H2Z".to_string()));
+/// # assert_eq!(first_contour_path(&[vec![ 0 ]], true), None);
+/// if let Some(path) = first_contour_path(&bits, true) {
+///     println!("{}", path);
+/// }
+/// ```
+pub fn first_contour_path(bits: &[Vec<i8>], closepaths: bool) -> Option<String> {
+    let mut path: Option<String> = None;
+    super::grid::scan_while(&mut bits_grid(bits), |_, points| {
+        let mut paths = String::new();
+        super::contour::push_path(&mut paths, points, closepaths);
+        path = Some(paths);
+        false
+    });
+    path
+}
+
+/// A function that takes a 2D array of bits of a tile, the bits of the adjacent tiles and an option as input and
+/// return a string of SVG Path commands as output.
+///
+/// The tile is traced as if the one bit wide borders of the adjacent tiles were present, so there are no artificial edges
+/// along the sides of the tile where a shape continues in an adjacent tile. Only the edges along the foreground bits
+/// of the tile are emitted, so the paths of all the tiles draw each edge of the whole image exactly once. The contours which are cut by the sides of the tile
+/// are emitted as open paths, the **closepaths option** applies only to the contours which belong entirely to the tile.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths_with_borders, TileBorders};
+/// // The right part of a 3x2 rectangle which continues in the tile on the left
+/// let bits = vec![vec![ 1,1,0 ],
+///                 vec![ 1,1,0 ],
+///                 vec![ 0,0,0 ]];
+/// let borders = TileBorders { left: vec![ 1,1,0 ], ..Default::default() };
+///
+/// # assert_eq!(bits_to_paths_with_borders(&bits, &borders, true), "M0 0H2V2H0");
+/// # assert_eq!(bits_to_paths_with_borders(&bits, &TileBorders::default(), true), "M0 0H2V2H0Z");
+/// println!("{}", bits_to_paths_with_borders(&bits, &borders, true));
+/// ```
+pub fn bits_to_paths_with_borders(bits: &[Vec<i8>], borders: &TileBorders, closepaths: bool) -> String {
+    let (rows, cols) = (bits.len(), bits[0].len());
+    let bit = |strip: &[i8], i: usize| strip.get(i) == Some(&1);
+    let mut contours = super::grid::bordered_par(rows + 2, cols + 2, |r, c| match (r, c) {
+        (0, _) => bit(&borders.top, c),
+        (r, _) if r == rows + 1 => bit(&borders.bottom, c),
+        (r, 0) => bit(&borders.left, r - 1),
+        (r, c) if c == cols + 1 => bit(&borders.right, r - 1),
+        (r, c) => bits[r - 1][c - 1] == 1,
+    });
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| {
+        let ring: Vec<(i32, i32)> = points.iter().map(|p| (p.0 - 1, p.1 - 1)).collect();
+        push_tile_edges(&mut paths, &ring, rows as i32, cols as i32, closepaths);
+    });
+    paths
+}
+
+// Push the edges of a contour which belong to a tile: the edges along a foreground bit of the tile
+fn push_tile_edges(paths: &mut String, ring: &[(i32, i32)], rows: i32, cols: i32, closepaths: bool) {
+    let n = ring.len();
+    let mut pieces: Vec<((i32, i32), (i32, i32))> = Vec::new();
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        let piece = if a.0 == b.0 {
+            let x = if b.1 > a.1 { a.0 - 1 } else { a.0 }; // The foreground is on the right-hand side
+            let (y0, y1) = (a.1.max(0).min(rows), b.1.max(0).min(rows));
+            if x >= 0 && x < cols && y0 != y1 { Some(((a.0, y0), (a.0, y1))) } else { None }
+        } else {
+            let y = if b.0 > a.0 { a.1 } else { a.1 - 1 };
+            let (x0, x1) = (a.0.max(0).min(cols), b.0.max(0).min(cols));
+            if y >= 0 && y < rows && x0 != x1 { Some(((x0, a.1), (x1, a.1))) } else { None }
+        };
+        pieces.extend(piece);
+    }
+    if pieces.len() == n {
+        super::contour::push_path(paths, ring, closepaths);
+        return;
+    }
+    for run in super::contour::runs(pieces) {
+        super::contour::push_path(paths, &run, false);
+    }
+}
+
+/// A function that takes a 2D array of bits as input and return an upper bound of the length of the string of SVG Path commands
+/// (with the **closepaths option** set to **true**) as output, e.g. to pre-allocate a buffer.
+///
+/// The vertices of the contours are counted exactly with a single scan (a vertex is where 1 or 3 of the 4 bits around it are
+/// foreground, and two vertices are where 2 diagonal bits are foreground), each vertex being one SVG Path command of at most
+/// as many digits as the largest coordinate.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::{bits_to_paths, estimate_path_len};
+/// let bits = vec![vec![ 1,1,1 ],
+///                 vec![ 1,0,1 ],
+///                 vec![ 1,1,1 ]];
+///
+/// let mut paths = String::with_capacity(estimate_path_len(&bits));
+/// paths.push_str(&bits_to_paths(bits.to_vec(), true));
+/// # assert_eq!(estimate_path_len(&bits), 22);
+/// # assert!(paths.len() <= estimate_path_len(&bits));
+/// ```
+pub fn estimate_path_len(bits: &[Vec<i8>]) -> usize {
+    path_len(bits, 1)
+}
+
+// The length of the SVG Path commands for the vertices between the rows y - 1 and y, for every step-th y (extrapolated)
+fn path_len(bits: &[Vec<i8>], step: usize) -> usize {
+    let rows = bits.len();
+    let cols = bits.first().map_or(0, |row| row.len());
+    let background = vec![0i8; cols];
+    let row = |y: usize| -> &[i8] { if y > 0 && y <= rows { &bits[y - 1] } else { &background } };
+    let mut vertices: usize = 0;
+    for y in (0..=rows).step_by(step) {
+        // The 4 bits around a vertex: a (top left), b (top right), c (bottom left) and d (bottom right)
+        let (mut a, mut c) = (false, false);
+        for (b, d) in row(y).iter().map(|&bit| bit == 1).zip(row(y + 1).iter().map(|&bit| bit == 1)).chain(std::iter::once((false, false))) {
+            vertices += (a ^ b ^ c ^ d) as usize + 2 * (a == d && b == c && a != b) as usize;
+            a = b;
+            c = d;
+        }
+    }
+    let vertices = vertices * step;
+    let digits = rows.max(cols).to_string().len();
+    let paths = vertices / 4;
+    vertices * (1 + digits) + paths * (2 + digits) // A command for each vertex, then the y coordinate of the M command and the Z command of each path
+}
+
+/// An iterator over the SVG Path commands of the contours of a 2D array of bits, see [`bits_to_path_fragments`].
+#[derive(Clone, Debug)]
+pub struct PathFragments {
+    contours: Vec<Vec<i8>>,
+    state: super::grid::ScanState,
+    points: Vec<(i32, i32)>,
+    closepaths: bool,
+}
+
+impl Iterator for PathFragments {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.state.next(&mut self.contours, &mut self.points)?;
+        let mut path = String::new();
+        super::contour::push_path(&mut path, &self.points, self.closepaths);
+        Some(path)
+    }
+}
+
+/// Each poll traces the next contour and is always ready: the scan runs in the task polling the stream,
+/// so a large array of bits can be traced on a blocking pool while the fragments are sent.
+#[cfg(feature = "stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+impl futures_core::Stream for PathFragments {
+    type Item = String;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, _: &mut std::task::Context<'_>) -> std::task::Poll<Option<String>> {
+        std::task::Poll::Ready(self.next())
+    }
+}
+
+/// A function that takes a 2D array of bits and an option as input and return the SVG Path commands of each outline
+/// with its holes and its id as output, e.g. to give each object its own `<path id="obj-{id}">` element.
+///
+/// The ids are assigned to the outlines in the order of the scan, starting at 0, and each hole has the id of the outline
+/// around it, so its commands follow the ones of this outline in the same string (the islands in a hole are other outlines).
+/// They only depend on the array of bits, and the concatenation of the strings is the string of [`bits_to_paths`] up to the order.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_labeled_paths;
+/// let bits = vec![vec![ 1,1,1,0,1 ],
+///                 vec![ 1,0,1,0,0 ],
+///                 vec![ 1,1,1,0,0 ]];
+///
+/// let paths = bits_to_labeled_paths(&bits, true);
+/// # assert_eq!(paths, vec![(0, "M0 0H3V3H0ZM1 1V2H2V1Z".to_string()), (1, "M4 0H5V1H4Z".to_string())]);
+/// for (id, path) in paths {
+///     println!(r#"<path id="obj-{}" d="{}"/>"#, id, path);
+/// }
+/// ```
+pub fn bits_to_labeled_paths(bits: &[Vec<i8>], closepaths: bool) -> Vec<(u32, String)> {
+    let contours = bits_to_contours(bits);
+    let mut ids: Vec<u32> = Vec::with_capacity(contours.len());
+    let mut paths: Vec<(u32, String)> = Vec::new();
+    for (contour, parent) in contours.iter().zip(super::contour::parents(&contours)) {
+        let id = match parent {
+            Some(p) if contour.is_hole => ids[p], // The outline around a hole is found before it
+            _ => {
+                paths.push((paths.len() as u32, String::new()));
+                paths.len() as u32 - 1
+            }
+        };
+        super::contour::push_path(&mut paths[id as usize].1, &contour.points, closepaths);
+        ids.push(id);
+    }
+    paths
+}
+
+/// A function that takes a 2D array of bits and an option as input and return an iterator over the SVG Path commands
+/// of each contour as output, which is also a `futures::Stream` with the **stream** feature.
+///
+/// The contours are traced incrementally: each call to `next` (or each poll of the stream) resumes the scan until the next contour,
+/// so the first paths can be sent (e.g. in an HTTP response) before the whole array of bits is traced. The concatenation of the paths
+/// is the string of [`bits_to_paths`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::array::bits_to_path_fragments;
+/// let bits = vec![vec![ 1,0,1 ]];
+///
+/// let mut fragments = bits_to_path_fragments(&bits, true);
+/// # assert_eq!(fragments.next(), Some("M0 0H1V1H0Z".to_string()));
+/// # assert_eq!(fragments.next(), Some("M2 0H3V1H2Z".to_string()));
+/// # assert_eq!(fragments.next(), None);
+/// for path in bits_to_path_fragments(&bits, true) {
+///     print!("{}", path);
+/// }
+/// ```
+pub fn bits_to_path_fragments(bits: &[Vec<i8>], closepaths: bool) -> PathFragments {
+    PathFragments { contours: bits_grid(bits), state: super::grid::ScanState::new(), points: Vec::new(), closepaths }
+}
+
+fn bits_grid(bits: &[Vec<i8>]) -> Vec<Vec<i8>> {
+    super::grid::bordered_par(bits.len(), bits[0].len(), |r, c| bits[r][c] == 1)
+}
+
+// A coordinate multiplied by a scale, rounded to some decimals without the trailing zeros (and without a negative zero)
+fn scaled(coordinate: i32, scale: f64, precision: u8) -> String {
+    number(coordinate as f64 * scale, precision)
+}
+
+/*
+ Append a contour with its corners cut at the given length (clamped to half of each edge), by a line or a quarter-circle arc
+*/
+fn push_styled_path(paths: &mut String, points: &[(i32, i32)], closepaths: bool, length: f64, round: bool, precision: u8) {
+    let n = points.len();
+    let edge = |i: usize| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        ((b.0 - a.0).signum(), (b.1 - a.1).signum(), ((b.0 - a.0).abs() + (b.1 - a.1).abs()) as f64)
+    };
+    // The entry and exit points of the corner of each vertex, its clamped length and if it turns clockwise
+    let corners: Vec<_> = (0..n).map(|i| {
+        let (p, (ix, iy, a), (ox, oy, b)) = (points[i], edge((i + n - 1) % n), edge(i));
+        let r = length.min(a / 2.0).min(b / 2.0);
+        let (x, y) = (p.0 as f64, p.1 as f64);
+        ((x - r * ix as f64, y - r * iy as f64), (x + r * ox as f64, y + r * oy as f64), r, ix * oy - iy * ox > 0)
+    }).collect();
+    let mut previous = corners[0].1;
+    paths.push_str(&format!("M{} {}", number(previous.0, precision), number(previous.1, precision)));
+    for &(entry, exit, r, clockwise) in corners[1..].iter().chain(std::iter::once(&corners[0])) {
+        if entry != previous {
+            if entry.1 == previous.1 { paths.push_str(&format!("H{}", number(entry.0, precision))); } else { paths.push_str(&format!("V{}", number(entry.1, precision))); }
+        }
+        if round {
+            paths.push_str(&format!("A{} {} 0 0 {} {} {}", number(r, precision), number(r, precision), clockwise as u8, number(exit.0, precision), number(exit.1, precision)));
+        } else {
+            paths.push_str(&format!("L{} {}", number(exit.0, precision), number(exit.1, precision)));
         }
-        o.rotate_left(rot.rem_euclid(8) as usize);
-        vertices_nbr += 1;
-        if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as usize))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as usize))); }
+        previous = exit;
     }
     if closepaths { paths.push('Z'); }
 }
@@ -0,0 +1,1311 @@
+/*
+ * Contour tracing library
+ * https://github.com/STPR/contour_tracing
+ *
+ * Copyright (c) 2022, STPR - https://github.com/STPR
+ *
+ * SPDX-License-Identifier: EUPL-1.2
+ */
+
+use std::collections::{HashMap, HashSet};
+
+/// A traced contour: the ordered vertices of a closed rectilinear polygon.
+///
+/// - **Outlines** are in **clockwise direction**
+/// - **Holes** are in **counterclockwise direction**
+///
+/// The last vertex is implicitly connected to the first one (it is not repeated). The first vertex of a traced
+/// contour is always its top left vertex (the leftmost of its topmost vertices): the tracer enters each contour there,
+/// whatever the scan order, so the output is deterministic and stable even for symmetric shapes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contour {
+    /// The vertices of the contour, in pixel coordinates
+    pub points: Vec<(i32, i32)>,
+    /// `true` if the contour is a hole, `false` if it is an outline
+    pub is_hole: bool,
+    /// `true` if the contour was traced with the **closepaths option**: its SVG Path has a closing **Z** command.
+    /// A contour is always a closed ring, this field only tells if the consumer should explicitly close it.
+    pub explicitly_closed: bool,
+}
+
+/// A traced contour whose vertices are borrowed from an arena shared by all the contours of a trace.
+///
+/// It is the same as a [`Contour`] (same directions and first vertex), without the allocation of a vec for each contour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ContourRef<'a> {
+    /// The vertices of the contour, in pixel coordinates
+    pub points: &'a [(i32, i32)],
+    /// `true` if the contour is a hole, `false` if it is an outline
+    pub is_hole: bool,
+}
+
+impl<'a> ContourRef<'a> {
+    /// Copy the vertices into an owned [`Contour`], not explicitly closed.
+    pub fn to_contour(&self) -> Contour {
+        Contour::new(self.points.to_vec(), self.is_hole)
+    }
+}
+
+/// The error of [`from_bytes`]: the bytes are not a valid binary encoding of contours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes do not start with the magic bytes `CTB`
+    BadMagic,
+    /// The version of the format is not supported (a newer version)
+    UnsupportedVersion(u8),
+    /// The bytes end in the middle of a value
+    Truncated,
+    /// A value does not fit in its type
+    Overflow,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not a binary encoding of contours"),
+            DecodeError::UnsupportedVersion(version) => write!(f, "unsupported version {} of the binary encoding of contours", version),
+            DecodeError::Truncated => write!(f, "truncated binary encoding of contours"),
+            DecodeError::Overflow => write!(f, "out of range value in the binary encoding of contours"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The differences between two sets of contours, see [`diff_contour_sets`].
+///
+/// The contours are given by their indexes in the reference set and in the current set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContourSetDiff {
+    /// The contours of the current set which match no contour of the reference set
+    pub added: Vec<usize>,
+    /// The contours of the reference set which match no contour of the current set
+    pub removed: Vec<usize>,
+    /// The matching contours `(reference, current)` which are different
+    pub changed: Vec<(usize, usize)>,
+    /// The matching contours `(reference, current)` which are the same
+    pub unchanged: Vec<(usize, usize)>,
+}
+
+/// A bounding box in pixel coordinates (the maximum coordinates are exclusive for the pixels, inclusive for the vertices).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BBox {
+    /// Minimum x coordinate
+    pub min_x: i32,
+    /// Minimum y coordinate
+    pub min_y: i32,
+    /// Maximum x coordinate
+    pub max_x: i32,
+    /// Maximum y coordinate
+    pub max_y: i32,
+}
+
+/// The bounding box of a contour with the metrics derived from it, see [`Contour::bbox_metrics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BBoxMetrics {
+    /// The bounding box of the vertices
+    pub bbox: BBox,
+    /// The width of the bounding box: `max_x - min_x`
+    pub width: i32,
+    /// The height of the bounding box: `max_y - min_y`
+    pub height: i32,
+    /// The aspect ratio: `width / height`, **0** if the width or the height is zero
+    pub aspect_ratio: f64,
+    /// The extent: the absolute area enclosed by the contour divided by `width * height`, from **0** to **1**,
+    /// **0** if the width or the height is zero
+    pub extent: f64,
+}
+
+/// The metadata of a traced contour, e.g. to drop the small speckles before the serialization.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContourMeta {
+    /// The signed area (shoelace formula): positive for an outline (clockwise), negative for a hole (counterclockwise)
+    pub signed_area: f64,
+    /// `true` if the contour is a hole, `false` if it is an outline
+    pub is_hole: bool,
+    /// The number of vertices
+    pub vertex_count: usize,
+    /// The bounding box of the vertices
+    pub bbox: BBox,
+    /// The two extreme vertices along the principal axis, see [`Contour::principal_endpoints`]
+    pub principal_endpoints: Option<((i32, i32), (i32, i32))>,
+}
+
+/// An outline with its holes: a node of the outline/hole hierarchy.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutlineNode {
+    /// The id of the connected component, in scan order
+    pub id: usize,
+    /// The outline
+    pub outline: Contour,
+    /// The holes directly inside the outline
+    pub holes: Vec<HoleNode>,
+}
+
+/// A hole with the outlines inside it (islands): a node of the outline/hole hierarchy.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HoleNode {
+    /// The hole
+    pub hole: Contour,
+    /// The outlines directly inside the hole
+    pub outlines: Vec<OutlineNode>,
+}
+
+/// A polygon as an exterior ring and its interior rings, each ring being closed (its first vertex is repeated at the end).
+pub type PolygonRings = (Vec<(i32, i32)>, Vec<Vec<(i32, i32)>>);
+
+/// A straight edge of the boundaries of several sets of contours, see [`shared_edges`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundaryEdge {
+    /// The first vertex of the edge
+    pub start: (i32, i32),
+    /// The last vertex of the edge
+    pub end: (i32, i32),
+    /// The index of the set of contours on the right-hand side of the edge (from `start` to `end`)
+    pub right: usize,
+    /// The index of the set of contours on the left-hand side of the edge, `None` if no set is on this side
+    pub left: Option<usize>,
+}
+
+/// The boundary of the regions which touch the sides of the image (the frame), along the sides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameBoundary {
+    /// The edges along the sides are traced like the other edges, so a region which touches the four sides is outlined
+    /// by the image rectangle (the default)
+    Closed,
+    /// The edges along the sides are not traced, as if the regions went on beyond the sides: the contours which touch
+    /// the sides are split into open paths (without the **Z** command), the other contours are unchanged
+    Open,
+}
+
+impl Default for FrameBoundary {
+    fn default() -> Self {
+        FrameBoundary::Closed
+    }
+}
+
+impl BBox {
+    /// Return the width of the bounding box: `max_x - min_x`.
+    pub fn width(&self) -> i32 {
+        self.max_x - self.min_x
+    }
+
+    /// Return the height of the bounding box: `max_y - min_y`.
+    pub fn height(&self) -> i32 {
+        self.max_y - self.min_y
+    }
+}
+
+impl OutlineNode {
+    /// Return the area of the connected component: the area of the outline minus the areas of its holes.
+    pub fn area(&self) -> i64 {
+        self.outline.signed_area() + self.holes.iter().map(|h| h.hole.signed_area()).sum::<i64>()
+    }
+
+    /// Return the polygons of the connected component and of the outlines inside its holes (islands), as closed rings.
+    ///
+    /// The exterior rings are the outlines, with a positive signed area, and the interior rings the holes, with a negative one
+    /// (see `OutlineNode::into_geo` for the `geo` types with the geo feature).
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::{Contour, HoleNode, OutlineNode};
+    /// let node = OutlineNode {
+    ///     id: 0,
+    ///     outline: Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false),
+    ///     holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true), outlines: vec![] }],
+    /// };
+    ///
+    /// assert_eq!(node.polygon_rings(), vec![(vec![(0, 0), (3, 0), (3, 3), (0, 3), (0, 0)], vec![vec![(1, 1), (1, 2), (2, 2), (2, 1), (1, 1)]])]);
+    /// ```
+    pub fn polygon_rings(&self) -> Vec<PolygonRings> {
+        let mut polygons = vec![(closed_ring(&self.outline.points), self.holes.iter().map(|h| closed_ring(&h.hole.points)).collect())];
+        for hole in &self.holes {
+            for island in &hole.outlines {
+                polygons.extend(island.polygon_rings());
+            }
+        }
+        polygons
+    }
+
+    /// Convert the connected component and the outlines inside its holes (islands) into a `geo_types::MultiPolygon`,
+    /// with the polygons of [`OutlineNode::polygon_rings`].
+    ///
+    /// The winding follows the conventions of the `geo` crate: the coordinates are kept, and there an outline is counterclockwise
+    /// (a positive signed area, as in a y-up space) and a hole clockwise, so the rings are not reversed. It is the same as the
+    /// `From` implementations, which also convert the coordinates to `f64` for the algorithms of `geo` which need floats.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::{Contour, HoleNode, OutlineNode};
+    /// let node = OutlineNode {
+    ///     id: 0,
+    ///     outline: Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false),
+    ///     holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true), outlines: vec![] }],
+    /// };
+    ///
+    /// let floats = geo_types::MultiPolygon::<f64>::from(&node);
+    /// let polygons = node.into_geo();
+    /// # assert_eq!(polygons.0[0].exterior().0.len(), 5);
+    /// # assert_eq!(polygons.0[0].interiors()[0].0[1], geo_types::Coord { x: 1, y: 2 });
+    /// # assert_eq!(floats.0[0].exterior().0[1], geo_types::Coord { x: 3.0, y: 0.0 });
+    /// println!("{:?} {:?}", polygons, floats);
+    /// ```
+    #[cfg(feature = "geo")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+    pub fn into_geo(self) -> geo_types::MultiPolygon<i32> {
+        geo_types::MultiPolygon::from(&self)
+    }
+
+    /// Return a list of triangles which covers exactly the connected component and the outlines inside its holes (islands).
+    ///
+    /// The region is split into trapezoids between the vertices (so the holes are never filled), and each trapezoid into
+    /// two triangles. All the triangles are in clockwise direction (like the outlines), so none is dropped by a back-face culling
+    /// which keeps them, and the sum of their areas is the area of the region.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::{Contour, HoleNode, OutlineNode};
+    /// let node = OutlineNode {
+    ///     id: 0,
+    ///     outline: Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false),
+    ///     holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true), outlines: vec![] }],
+    /// };
+    ///
+    /// let triangles = node.triangles();
+    /// # assert_eq!(triangles.len(), 8);
+    /// # assert_eq!(triangles[0], [(0.0, 0.0), (3.0, 0.0), (3.0, 1.0)]);
+    /// println!("{:?}", triangles);
+    /// ```
+    #[cfg(feature = "tessellation")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tessellation")))]
+    pub fn triangles(&self) -> Vec<[(f32, f32); 3]> {
+        let mut rings: Vec<&[(i32, i32)]> = Vec::new();
+        let mut stack: Vec<&OutlineNode> = vec![self];
+        while let Some(node) = stack.pop() {
+            rings.push(&node.outline.points);
+            for hole in &node.holes {
+                rings.push(&hole.hole.points);
+                stack.extend(hole.outlines.iter());
+            }
+        }
+        trapezoids(&rings)
+    }
+}
+
+#[cfg(feature = "geo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl<T: geo_types::CoordNum + From<i32>> From<&Contour> for geo_types::LineString<T> {
+    /// Convert a contour into a closed ring, without changing its direction.
+    fn from(contour: &Contour) -> Self {
+        geo_ring(closed_ring(&contour.points))
+    }
+}
+
+#[cfg(feature = "geo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl<T: geo_types::CoordNum + From<i32>> From<&OutlineNode> for geo_types::MultiPolygon<T> {
+    /// Convert a connected component and its islands into polygons, see [`OutlineNode::into_geo`].
+    fn from(node: &OutlineNode) -> Self {
+        geo_types::MultiPolygon(node.polygon_rings().into_iter().map(geo_polygon).collect())
+    }
+}
+
+#[cfg(feature = "geo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+impl<T: geo_types::CoordNum + From<i32>> From<OutlineNode> for geo_types::MultiPolygon<T> {
+    /// Convert a connected component and its islands into polygons, see [`OutlineNode::into_geo`].
+    fn from(node: OutlineNode) -> Self {
+        geo_types::MultiPolygon::from(&node)
+    }
+}
+
+/*
+ A polygon of the geo crate with the rings of a polygon, converting the coordinates
+*/
+#[cfg(feature = "geo")]
+pub(crate) fn geo_polygon<T: geo_types::CoordNum + From<i32>>((exterior, interiors): PolygonRings) -> geo_types::Polygon<T> {
+    geo_types::Polygon::new(geo_ring(exterior), interiors.into_iter().map(geo_ring).collect())
+}
+
+#[cfg(feature = "geo")]
+fn geo_ring<T: geo_types::CoordNum + From<i32>>(ring: Vec<(i32, i32)>) -> geo_types::LineString<T> {
+    geo_types::LineString::from(ring.into_iter().map(|(x, y)| (x.into(), y.into())).collect::<Vec<(T, T)>>())
+}
+
+/*
+ Split the region of some rings (nonzero winding rule) into horizontal trapezoids between the y coordinates of the vertices,
+ each trapezoid being split into two clockwise triangles
+*/
+#[cfg(feature = "tessellation")]
+fn trapezoids(rings: &[&[(i32, i32)]]) -> Vec<[(f32, f32); 3]> {
+    let mut edges = Vec::new(); // Top vertex, bottom vertex and direction
+    let mut ys: Vec<i32> = Vec::new();
+    for ring in rings {
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            ys.push(a.1);
+            if a.1 != b.1 {
+                let (top, bottom, dir) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+                edges.push(((top.0 as f64, top.1 as f64), (bottom.0 as f64, bottom.1 as f64), dir));
+            }
+        }
+    }
+    ys.sort_unstable();
+    ys.dedup();
+    let x_at = |e: &((f64, f64), (f64, f64), i32), y: f64| e.0 .0 + (e.1 .0 - e.0 .0) * (y - e.0 .1) / (e.1 .1 - e.0 .1);
+    let mut triangles: Vec<[(f32, f32); 3]> = Vec::new();
+    for band in ys.windows(2) {
+        let (ya, yb) = (band[0] as f64, band[1] as f64);
+        let mut crossing: Vec<(f64, f64, f64, i32)> = edges.iter()
+            .filter(|e| e.0 .1 <= ya && e.1 .1 >= yb)
+            .map(|e| (x_at(e, (ya + yb) / 2.0), x_at(e, ya), x_at(e, yb), e.2))
+            .collect();
+        crossing.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut winding = 0;
+        let mut left = (0.0, 0.0);
+        for &(_, xa, xb, dir) in &crossing {
+            if winding == 0 {
+                left = (xa, xb);
+            }
+            winding += dir;
+            if winding == 0 {
+                let (tl, tr, br, bl) = ((left.0 as f32, ya as f32), (xa as f32, ya as f32), (xb as f32, yb as f32), (left.1 as f32, yb as f32));
+                if tl != tr {
+                    triangles.push([tl, tr, br]);
+                }
+                if br != bl {
+                    triangles.push([tl, br, bl]);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+fn closed_ring(points: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let mut ring = points.to_vec();
+    if let Some(&first) = points.first() {
+        ring.push(first);
+    }
+    ring
+}
+
+impl Contour {
+    /// Create a contour from its vertices, not explicitly closed.
+    pub fn new(points: Vec<(i32, i32)>, is_hole: bool) -> Self {
+        Contour { points, is_hole, explicitly_closed: false }
+    }
+
+    /// Return the signed area of the contour (shoelace formula): positive for an outline (clockwise), negative for a hole (counterclockwise).
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// assert_eq!(Contour::new(vec![(0, 0), (3, 0), (3, 2), (0, 2)], false).signed_area(), 6);
+    /// assert_eq!(Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true).signed_area(), -1);
+    /// ```
+    pub fn signed_area(&self) -> i64 {
+        ring_signed_area(&self.points)
+    }
+
+    /// Return the bounding box of the vertices, or `None` if the contour has no vertices.
+    pub fn bbox(&self) -> Option<BBox> {
+        let first = self.points.first()?;
+        Some(self.points.iter().fold(BBox { min_x: first.0, min_y: first.1, max_x: first.0, max_y: first.1 }, |b, p| {
+            BBox { min_x: b.min_x.min(p.0), min_y: b.min_y.min(p.1), max_x: b.max_x.max(p.0), max_y: b.max_y.max(p.1) }
+        }))
+    }
+
+    /// Return the [bounding box](Contour::bbox) of the contour with its width, height, aspect ratio and extent,
+    /// or `None` if the contour has no vertices. A thin line has an aspect ratio far from **1** and a low extent,
+    /// a rectangle has an extent of **1**.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// // An L shape
+    /// let metrics = Contour::new(vec![(0, 0), (1, 0), (1, 2), (4, 2), (4, 3), (0, 3)], false).bbox_metrics().unwrap();
+    ///
+    /// assert_eq!((metrics.width, metrics.height), (4, 3));
+    /// assert_eq!(metrics.aspect_ratio, 4.0 / 3.0);
+    /// assert_eq!(metrics.extent, 6.0 / 12.0);
+    /// ```
+    pub fn bbox_metrics(&self) -> Option<BBoxMetrics> {
+        let bbox = self.bbox()?;
+        let (width, height) = (bbox.width(), bbox.height());
+        let (aspect_ratio, extent) = if width == 0 || height == 0 {
+            (0.0, 0.0)
+        } else {
+            (width as f64 / height as f64, self.signed_area().abs() as f64 / (width as i64 * height as i64) as f64)
+        };
+        Some(BBoxMetrics { bbox, width, height, aspect_ratio, extent })
+    }
+
+    /// Simplify the contour using the Ramer–Douglas–Peucker algorithm.
+    ///
+    /// The vertices which are within `epsilon` pixels of the simplified polygon are removed, the order of the remaining vertices
+    /// (so the direction of the contour) is kept and at least 3 vertices are kept. With an `epsilon` of **0.0**, the contour is unchanged.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// let staircase = Contour::new(vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)], false);
+    ///
+    /// assert_eq!(staircase.simplify(0.0), staircase);
+    /// assert_eq!(staircase.simplify(1.0).points, vec![(0, 0), (2, 2), (0, 2)]);
+    /// ```
+    pub fn simplify(&self, epsilon: f64) -> Contour {
+        let n = self.points.len();
+        if n < 4 || epsilon <= 0.0 {
+            return self.clone();
+        }
+        let far = (1..n).max_by_key(|&i| distance2(self.points[0], self.points[i])).unwrap_or(1); // Split the ring into two polylines
+        let mut keep = vec![false; n + 1];
+        keep[0] = true;
+        keep[far] = true;
+        let mut ring = self.points.clone();
+        ring.push(self.points[0]);
+        rdp(&ring, 0, far, epsilon, &mut keep);
+        rdp(&ring, far, n, epsilon, &mut keep);
+        if keep.iter().filter(|&&k| k).count() < 3 { // Keep at least a triangle
+            let third = (1..n).filter(|&i| i != far).max_by(|&i, &j| distance(self.points[0], self.points[far], self.points[i]).partial_cmp(&distance(self.points[0], self.points[far], self.points[j])).unwrap_or(std::cmp::Ordering::Equal));
+            keep[third.unwrap_or(1)] = true;
+        }
+        Contour { points: (0..n).filter(|&i| keep[i]).map(|i| self.points[i]).collect(), is_hole: self.is_hole, explicitly_closed: self.explicitly_closed }
+    }
+
+    /// Return the two extreme vertices of the contour along its principal axis, e.g. the approximate endpoints of a stroke.
+    ///
+    /// The principal axis is computed with a principal component analysis (PCA) on the vertices.
+    /// Returns `None` if the contour has no vertices.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// let stroke = Contour::new(vec![(0, 0), (5, 0), (5, 1), (0, 1)], false);
+    ///
+    /// assert_eq!(stroke.principal_endpoints(), Some(((0, 0), (5, 1))));
+    /// ```
+    pub fn principal_endpoints(&self) -> Option<((i32, i32), (i32, i32))> {
+        let n = self.points.len() as f64;
+        let mean_x = self.points.iter().map(|p| p.0 as f64).sum::<f64>() / n;
+        let mean_y = self.points.iter().map(|p| p.1 as f64).sum::<f64>() / n;
+        let (mut cxx, mut cxy, mut cyy) = (0.0, 0.0, 0.0);
+        for p in &self.points {
+            let (dx, dy) = (p.0 as f64 - mean_x, p.1 as f64 - mean_y);
+            cxx += dx * dx;
+            cxy += dx * dy;
+            cyy += dy * dy;
+        }
+        let angle = 0.5 * (2.0 * cxy).atan2(cxx - cyy);
+        let projection = |p: &&(i32, i32)| p.0 as f64 * angle.cos() + p.1 as f64 * angle.sin();
+        let min = self.points.iter().min_by(|a, b| projection(a).partial_cmp(&projection(b)).unwrap_or(std::cmp::Ordering::Equal))?;
+        let max = self.points.iter().max_by(|a, b| projection(a).partial_cmp(&projection(b)).unwrap_or(std::cmp::Ordering::Equal))?;
+        Some((*min, *max))
+    }
+
+    /// Rotate the vertices of the contour so that the first vertex is the lexicographically smallest `(x, y)` vertex.
+    ///
+    /// The direction of the contour is kept, so the normalized contours can be compared or hashed regardless of where the scan started them.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// let mut contour = Contour::new(vec![(1, 0), (2, 0), (2, 2), (0, 2), (0, 1), (1, 1)], false);
+    /// contour.normalize_start();
+    ///
+    /// assert_eq!(contour.points, vec![(0, 1), (1, 1), (1, 0), (2, 0), (2, 2), (0, 2)]);
+    /// ```
+    pub fn normalize_start(&mut self) {
+        if let Some(start) = (0..self.points.len()).min_by_key(|&i| self.points[i]) {
+            self.points.rotate_left(start);
+        }
+    }
+
+    /// Return the number of convex corners of the contour.
+    ///
+    /// A vertex is a convex corner if the contour turns in its own direction there (to the right for a clockwise outline,
+    /// to the left for a counterclockwise hole), so a rectangle has 4 convex corners whether it is an outline or a hole.
+    /// The collinear vertices are neither convex nor concave corners.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// // An L shape
+    /// let contour = Contour::new(vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)], false);
+    ///
+    /// assert_eq!(contour.convex_corners(), 5);
+    /// assert_eq!(contour.concave_corners(), 1);
+    /// ```
+    pub fn convex_corners(&self) -> usize {
+        self.corners().0
+    }
+
+    /// Return the number of concave corners of the contour, see [`Contour::convex_corners`].
+    pub fn concave_corners(&self) -> usize {
+        self.corners().1
+    }
+
+    fn corners(&self) -> (usize, usize) {
+        let n = self.points.len();
+        let direction = self.signed_area().signum();
+        (0..n).fold((0, 0), |(convex, concave), i| {
+            let (a, b, c) = (self.points[(i + n - 1) % n], self.points[i], self.points[(i + 1) % n]);
+            let turn = ((b.0 - a.0) as i64 * (c.1 - b.1) as i64 - (b.1 - a.1) as i64 * (c.0 - b.0) as i64).signum();
+            match turn * direction {
+                1 => (convex + 1, concave),
+                -1 => (convex, concave + 1),
+                _ => (convex, concave),
+            }
+        })
+    }
+
+    /// Return the seven Hu moment invariants of the region enclosed by the contour.
+    ///
+    /// The raw moments of the region are computed exactly from the vertices (Green's theorem), then normalized, so the moments
+    /// are invariant by translation, scaling and rotation. For a hole, the region is the hole itself. The seventh moment changes
+    /// its sign with a reflection (the y axis points down here). A contour with a zero area has only zero moments.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// let square = Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false);
+    ///
+    /// let hu = square.hu_moments();
+    /// # assert!((hu[0] - 1.0 / 6.0).abs() < 1e-12);
+    /// # assert!(hu[1..].iter().all(|h| h.abs() < 1e-12));
+    /// println!("{:?}", hu);
+    /// ```
+    pub fn hu_moments(&self) -> [f64; 7] {
+        let n = self.points.len();
+        let origin = match self.points.first() {
+            Some(&(x, y)) => (x as f64, y as f64),
+            None => return [0.0; 7],
+        };
+        // Raw moments m00, m10, m01, m20, m11, m02, m30, m21, m12, m03 relative to the first vertex
+        let mut m = [0.0f64; 10];
+        for i in 0..n {
+            let (x0, y0) = (self.points[i].0 as f64 - origin.0, self.points[i].1 as f64 - origin.1);
+            let (x1, y1) = (self.points[(i + 1) % n].0 as f64 - origin.0, self.points[(i + 1) % n].1 as f64 - origin.1);
+            let c = x0 * y1 - x1 * y0;
+            m[0] += c / 2.0;
+            m[1] += (x0 + x1) * c / 6.0;
+            m[2] += (y0 + y1) * c / 6.0;
+            m[3] += (x0 * x0 + x0 * x1 + x1 * x1) * c / 12.0;
+            m[4] += (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) * c / 24.0;
+            m[5] += (y0 * y0 + y0 * y1 + y1 * y1) * c / 12.0;
+            m[6] += (x0 * x0 * x0 + x0 * x0 * x1 + x0 * x1 * x1 + x1 * x1 * x1) * c / 20.0;
+            m[7] += (x0 * x0 * (3.0 * y0 + y1) + 2.0 * x0 * x1 * (y0 + y1) + x1 * x1 * (y0 + 3.0 * y1)) * c / 60.0;
+            m[8] += (y0 * y0 * (3.0 * x0 + x1) + 2.0 * y0 * y1 * (x0 + x1) + y1 * y1 * (x0 + 3.0 * x1)) * c / 60.0;
+            m[9] += (y0 * y0 * y0 + y0 * y0 * y1 + y0 * y1 * y1 + y1 * y1 * y1) * c / 20.0;
+        }
+        if m[0] == 0.0 {
+            return [0.0; 7];
+        }
+        if m[0] < 0.0 { // A counterclockwise contour
+            for v in &mut m {
+                *v = -*v;
+            }
+        }
+        let (xc, yc) = (m[1] / m[0], m[2] / m[0]);
+        let eta = |mu: f64, order: i32| mu / m[0].powf(1.0 + order as f64 / 2.0);
+        let n20 = eta(m[3] - xc * m[1], 2);
+        let n11 = eta(m[4] - xc * m[2], 2);
+        let n02 = eta(m[5] - yc * m[2], 2);
+        let n30 = eta(m[6] - 3.0 * xc * m[3] + 2.0 * xc * xc * m[1], 3);
+        let n21 = eta(m[7] - 2.0 * xc * m[4] - yc * m[3] + 2.0 * xc * xc * m[2], 3);
+        let n12 = eta(m[8] - 2.0 * yc * m[4] - xc * m[5] + 2.0 * yc * yc * m[1], 3);
+        let n03 = eta(m[9] - 3.0 * yc * m[5] + 2.0 * yc * yc * m[2], 3);
+        let (a, b) = (n30 + n12, n21 + n03);
+        [
+            n20 + n02,
+            (n20 - n02).powi(2) + 4.0 * n11 * n11,
+            (n30 - 3.0 * n12).powi(2) + (3.0 * n21 - n03).powi(2),
+            a * a + b * b,
+            (n30 - 3.0 * n12) * a * (a * a - 3.0 * b * b) + (3.0 * n21 - n03) * b * (3.0 * a * a - b * b),
+            (n20 - n02) * (a * a - b * b) + 4.0 * n11 * a * b,
+            (3.0 * n21 - n03) * a * (a * a - 3.0 * b * b) - (n30 - 3.0 * n12) * b * (3.0 * a * a - b * b),
+        ]
+    }
+
+    /// Return the convex hull of the vertices of the contour, in clockwise direction (like the outlines) and starting at its
+    /// top left vertex, without collinear vertices.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// // An L shape
+    /// let contour = Contour::new(vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)], false);
+    ///
+    /// assert_eq!(contour.convex_hull(), vec![(0, 0), (1, 0), (2, 1), (2, 2), (0, 2)]);
+    /// ```
+    pub fn convex_hull(&self) -> Vec<(i32, i32)> {
+        let mut points = self.points.clone();
+        points.sort_unstable_by_key(|p| (p.1, p.0));
+        points.dedup();
+        if points.len() < 3 {
+            return points;
+        }
+        // Monotone chain from the top left vertex, the right-hand side chain then the left-hand side chain (clockwise with y down)
+        let cross = |o: (i32, i32), a: (i32, i32), b: (i32, i32)| (a.0 - o.0) as i64 * (b.1 - o.1) as i64 - (a.1 - o.1) as i64 * (b.0 - o.0) as i64;
+        let n = points.len();
+        let mut hull: Vec<(i32, i32)> = Vec::new();
+        for pass in 0..2 {
+            let start = hull.len();
+            for p in (0..n).map(|i| if pass == 0 { points[i] } else { points[n - 1 - i] }) {
+                while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0 {
+                    hull.pop();
+                }
+                hull.push(p);
+            }
+            hull.pop();
+        }
+        hull
+    }
+
+    /// Return the solidity of the contour: the area of the region enclosed by the contour divided by the area of its
+    /// [convex hull](Contour::convex_hull), from **0** (very dented or spiky) to **1** (convex). For a hole, the region is the hole itself.
+    /// A contour with a zero area has a solidity of **0**.
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// // An L shape
+    /// let contour = Contour::new(vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)], false);
+    ///
+    /// assert_eq!(contour.solidity(), 3.0 / 3.5);
+    /// ```
+    pub fn solidity(&self) -> f64 {
+        let hull = ring_signed_area_x2(&self.convex_hull());
+        if hull == 0 { 0.0 } else { ring_signed_area_x2(&self.points).abs() as f64 / hull as f64 }
+    }
+
+    /// Offset (inset or outset) the contour by `delta` pixels.
+    ///
+    /// The region enclosed by the contour is grown by `delta` pixels if `delta` is positive (a dilation) or shrunk
+    /// if `delta` is negative (an erosion), with mitered (square) corners. For a hole, the region is the hole itself.
+    /// The offset region is traced again, so the returned contours keep the direction of this contour, and:
+    /// - an inset can split the contour into several contours, or make it vanish (an empty vec is returned)
+    /// - an outset can merge parts of the contour together, which can enclose new contours of the opposite kind
+    /// # Examples
+    /// ```edition2018
+    /// # use contour_tracing::contour::Contour;
+    /// let square = Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false);
+    ///
+    /// assert_eq!(square.offset(1), vec![Contour::new(vec![(-1, -1), (4, -1), (4, 4), (-1, 4)], false)]);
+    /// assert_eq!(square.offset(-1), vec![Contour::new(vec![(1, 1), (2, 1), (2, 2), (1, 2)], false)]);
+    /// assert_eq!(square.offset(-2), vec![]);
+    /// ```
+    pub fn offset(&self, delta: i32) -> Vec<Contour> {
+        let mut offset = offset_ring(&self.points, self.is_hole, delta);
+        for contour in &mut offset {
+            contour.explicitly_closed = self.explicitly_closed;
+        }
+        offset
+    }
+}
+
+const BYTES_MAGIC: &[u8; 3] = b"CTB";
+const BYTES_VERSION: u8 = 1;
+
+/// Encode some contours with their ids (e.g. the output of `array::bits_to_contours_with_ids`) in a compact binary format.
+///
+/// The format (version **1**) is the magic bytes `CTB`, the version byte, the number of contours, then for each contour its id,
+/// a flags byte (**1** for a hole, **2** if explicitly closed), its number of vertices, its first vertex and the deltas from each
+/// vertex to the next one. The numbers are LEB128 varints, the coordinates and the deltas being zigzag encoded, so the small steps
+/// of the pixel contours take a single byte. A decoder rejects the versions newer than its own, see [`from_bytes`].
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::contour::{from_bytes, to_bytes, Contour};
+/// let contours = vec![(0, Contour::new(vec![(0, 0), (3, 0), (3, 2), (0, 2)], false))];
+///
+/// let bytes = to_bytes(&contours);
+/// # assert_eq!(bytes.len(), 16);
+/// assert_eq!(from_bytes(&bytes), Ok(contours));
+/// ```
+pub fn to_bytes(contours: &[(usize, Contour)]) -> Vec<u8> {
+    fn varint(bytes: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+    let zigzag = |value: i64| ((value << 1) ^ (value >> 63)) as u64;
+    let mut bytes: Vec<u8> = BYTES_MAGIC.to_vec();
+    bytes.push(BYTES_VERSION);
+    varint(&mut bytes, contours.len() as u64);
+    for (id, contour) in contours {
+        varint(&mut bytes, *id as u64);
+        bytes.push(contour.is_hole as u8 | (contour.explicitly_closed as u8) << 1);
+        varint(&mut bytes, contour.points.len() as u64);
+        let mut previous = (0i64, 0i64);
+        for p in &contour.points {
+            let p = (p.0 as i64, p.1 as i64);
+            varint(&mut bytes, zigzag(p.0 - previous.0));
+            varint(&mut bytes, zigzag(p.1 - previous.1));
+            previous = p;
+        }
+    }
+    bytes
+}
+
+/// Decode some contours with their ids from the binary format of [`to_bytes`].
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<(usize, Contour)>, DecodeError> {
+    fn varint(bytes: &[u8], at: &mut usize) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = *bytes.get(*at).ok_or(DecodeError::Truncated)?;
+            *at += 1;
+            if shift == 63 && byte & 0x7f > 1 {
+                return Err(DecodeError::Overflow);
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::Overflow)
+    }
+    let unzigzag = |value: u64| (value >> 1) as i64 ^ -((value & 1) as i64);
+    if bytes.len() < 4 || &bytes[..3] != BYTES_MAGIC {
+        return Err(if bytes.len() < 4 && BYTES_MAGIC.starts_with(bytes) { DecodeError::Truncated } else { DecodeError::BadMagic });
+    }
+    if bytes[3] > BYTES_VERSION {
+        return Err(DecodeError::UnsupportedVersion(bytes[3]));
+    }
+    let mut at = 4;
+    let count = varint(bytes, &mut at)?;
+    let mut contours: Vec<(usize, Contour)> = Vec::new();
+    for _ in 0..count {
+        let id = varint(bytes, &mut at)?;
+        let flags = *bytes.get(at).ok_or(DecodeError::Truncated)?;
+        at += 1;
+        let n = varint(bytes, &mut at)?;
+        let mut points: Vec<(i32, i32)> = Vec::new();
+        let mut previous = (0i64, 0i64);
+        for _ in 0..n {
+            let p = (previous.0 + unzigzag(varint(bytes, &mut at)?), previous.1 + unzigzag(varint(bytes, &mut at)?));
+            if p.0 < i32::MIN as i64 || p.0 > i32::MAX as i64 || p.1 < i32::MIN as i64 || p.1 > i32::MAX as i64 {
+                return Err(DecodeError::Overflow);
+            }
+            points.push((p.0 as i32, p.1 as i32));
+            previous = p;
+        }
+        let mut contour = Contour::new(points, flags & 1 != 0);
+        contour.explicitly_closed = flags & 2 != 0;
+        contours.push((id as usize, contour));
+    }
+    Ok(contours)
+}
+
+/// Compare a reference set of contours and a current set of contours, e.g. the contours of two outputs of a model.
+///
+/// First, the contours which are the same (same kind and same vertices, whatever the first vertex) are matched as unchanged.
+/// Then, the remaining contours of the same kind are matched greedily, best first, by the overlap of their bounding boxes:
+/// two contours match (as changed) if the intersection of their bounding boxes is at least half of their union (IoU >= 0.5).
+/// The other contours are added or removed. All the vecs of the [`ContourSetDiff`] are sorted.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::contour::{diff_contour_sets, Contour, ContourSetDiff};
+/// let reference = vec![Contour::new(vec![(0, 0), (4, 0), (4, 4), (0, 4)], false), Contour::new(vec![(9, 0), (10, 0), (10, 1), (9, 1)], false)];
+/// let current = vec![Contour::new(vec![(0, 0), (4, 0), (4, 5), (0, 5)], false)];
+///
+/// let diff = diff_contour_sets(&reference, &current);
+/// assert_eq!(diff, ContourSetDiff { added: vec![], removed: vec![1], changed: vec![(0, 0)], unchanged: vec![] });
+/// ```
+pub fn diff_contour_sets(reference: &[Contour], current: &[Contour]) -> ContourSetDiff {
+    let fingerprint = |contour: &Contour| {
+        let mut normalized = Contour::new(contour.points.clone(), contour.is_hole);
+        normalized.normalize_start();
+        normalized
+    };
+    let mut diff = ContourSetDiff::default();
+    let mut unmatched: HashMap<Contour, Vec<usize>> = HashMap::new();
+    for (i, contour) in reference.iter().enumerate().rev() {
+        unmatched.entry(fingerprint(contour)).or_default().push(i);
+    }
+    let mut matched_reference = vec![false; reference.len()];
+    let mut remaining: Vec<usize> = Vec::new();
+    for (j, contour) in current.iter().enumerate() {
+        match unmatched.get_mut(&fingerprint(contour)).and_then(|indexes| indexes.pop()) {
+            Some(i) => {
+                matched_reference[i] = true;
+                diff.unchanged.push((i, j));
+            }
+            None => remaining.push(j),
+        }
+    }
+    let bboxes = |set: &[Contour]| -> Vec<BBox> { set.iter().map(|c| c.bbox().unwrap_or_default()).collect() };
+    let (reference_bboxes, current_bboxes) = (bboxes(reference), bboxes(current));
+    let area = |b: &BBox| (b.max_x - b.min_x) as i64 * (b.max_y - b.min_y) as i64;
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for i in (0..reference.len()).filter(|&i| !matched_reference[i]) {
+        for &j in remaining.iter().filter(|&&j| current[j].is_hole == reference[i].is_hole) {
+            let (a, b) = (&reference_bboxes[i], &current_bboxes[j]);
+            let (w, h) = (a.max_x.min(b.max_x) - a.min_x.max(b.min_x), a.max_y.min(b.max_y) - a.min_y.max(b.min_y));
+            let intersection = if w > 0 && h > 0 { w as i64 * h as i64 } else { 0 };
+            let union = area(a) + area(b) - intersection;
+            if union > 0 && 2 * intersection >= union {
+                candidates.push((intersection as f64 / union as f64, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal).then((a.1, a.2).cmp(&(b.1, b.2))));
+    let mut matched_current = vec![false; current.len()];
+    for (_, i, j) in candidates {
+        if !matched_reference[i] && !matched_current[j] {
+            matched_reference[i] = true;
+            matched_current[j] = true;
+            diff.changed.push((i, j));
+        }
+    }
+    diff.added = remaining.into_iter().filter(|&j| !matched_current[j]).collect();
+    diff.removed = (0..reference.len()).filter(|&i| !matched_reference[i]).collect();
+    diff.changed.sort_unstable();
+    diff.unchanged.sort_unstable();
+    diff
+}
+
+/// Merge several sets of contours traced separately (e.g. the tiles or the label layers of an image) into one canonical set.
+///
+/// Two contours are duplicates when they are the same contour: same kind (outline or hole) and same cycle of vertices in the same
+/// direction, whatever their first vertex and whether they are explicitly closed, like the unchanged contours of [`diff_contour_sets`].
+/// Only one of them is kept, e.g. a contour on a boundary shared by two tiles, so the contours cut by a boundary are not joined.
+/// The first vertex of each contour is its top left vertex (the minimum `y`, then the minimum `x`) and the contours are sorted
+/// as the scan of the tracers gives them: by first vertex (top, then left), then the outlines before the holes, then by vertices.
+/// The id of a contour is its index in the merged set, so the ids depend only on the contours, not on the order of the sets.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::contour::{merge_contour_sets, Contour};
+/// let shared = Contour::new(vec![(4, 0), (5, 0), (5, 1), (4, 1)], false);
+/// let left = vec![Contour::new(vec![(0, 0), (1, 0), (1, 1), (0, 1)], false), shared.clone()];
+/// let right = vec![Contour::new(vec![(5, 1), (4, 1), (4, 0), (5, 0)], false), Contour::new(vec![(8, 0), (9, 0), (9, 1), (8, 1)], false)];
+///
+/// let merged = merge_contour_sets(vec![right, left]);
+/// assert_eq!(merged.len(), 3);
+/// assert_eq!(merged[1], shared);
+/// ```
+pub fn merge_contour_sets(sets: Vec<Vec<Contour>>) -> Vec<Contour> {
+    let mut merged: Vec<Contour> = Vec::new();
+    let mut seen: HashSet<Contour> = HashSet::new();
+    for contour in sets.into_iter().flatten() {
+        let mut fingerprint = Contour::new(contour.points, contour.is_hole);
+        fingerprint.normalize_start();
+        if seen.insert(fingerprint.clone()) {
+            if let Some(first) = (0..fingerprint.points.len()).min_by_key(|&i| (fingerprint.points[i].1, fingerprint.points[i].0)) {
+                fingerprint.points.rotate_left(first);
+            }
+            merged.push(fingerprint);
+        }
+    }
+    merged.sort_by(|a, b| {
+        let start = |c: &Contour| c.points.first().map(|p| (p.1, p.0));
+        start(a).cmp(&start(b)).then(a.is_hole.cmp(&b.is_hole)).then_with(|| a.points.cmp(&b.points))
+    });
+    merged
+}
+
+/// Cross-check the classification of each contour (outline or hole) with its direction (the sign of its area):
+/// return the index of the first contour which is not clockwise if it is an outline, or not counterclockwise if it is a hole.
+///
+/// The tracer classifies the contours with the levels of the scan, which must always agree with their direction
+/// (the debug builds also assert it while tracing), so an error means a bug, or contours altered or built by hand.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::contour::{validate, Contour};
+/// let outline = Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false);
+/// let hole = Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true);
+///
+/// assert_eq!(validate(&[outline.clone(), hole.clone()]), Ok(()));
+/// assert_eq!(validate(&[outline, Contour { is_hole: false, ..hole }]), Err(1));
+/// ```
+pub fn validate(contours: &[Contour]) -> Result<(), usize> {
+    match contours.iter().position(|contour| (contour.signed_area() > 0) == contour.is_hole || contour.signed_area() == 0) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+/// Find the edges shared by several sets of contours, e.g. the contours of each label of a label map.
+///
+/// The region of a set of contours is on the right-hand side of its edges (the outlines are clockwise and the holes are counterclockwise),
+/// so where two regions abut, the same edge is in both sets with opposite directions. The edges of the contours are split into
+/// [`BoundaryEdge`]s where their neighbouring set changes. If `merge` is `true`, each shared edge is returned once
+/// (in the direction of its set with the smallest index), so the edges form a planar subdivision; otherwise the boundary of each set
+/// is returned whole. The edges are returned by set, contour and vertex order.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::contour::{shared_edges, BoundaryEdge, Contour};
+/// // Two abutting squares
+/// let left = vec![Contour::new(vec![(0, 0), (1, 0), (1, 1), (0, 1)], false)];
+/// let right = vec![Contour::new(vec![(1, 0), (2, 0), (2, 1), (1, 1)], false)];
+///
+/// let edges = shared_edges(&[left, right], true);
+/// # assert_eq!(edges.len(), 7);
+/// assert_eq!(edges[1], BoundaryEdge { start: (1, 0), end: (1, 1), right: 0, left: Some(1) });
+/// ```
+pub fn shared_edges(sets: &[Vec<Contour>], merge: bool) -> Vec<BoundaryEdge> {
+    let unit_edges = |points: &[(i32, i32)], i: usize| {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        let step = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+        let n = (b.0 - a.0).abs().max((b.1 - a.1).abs());
+        (0..n).map(move |k| ((a.0 + k * step.0, a.1 + k * step.1), (a.0 + (k + 1) * step.0, a.1 + (k + 1) * step.1)))
+    };
+    let mut owners = HashMap::new(); // The set of each directed unit edge
+    for (set, contours) in sets.iter().enumerate() {
+        for contour in contours {
+            for i in 0..contour.points.len() {
+                for edge in unit_edges(&contour.points, i) {
+                    owners.insert(edge, set);
+                }
+            }
+        }
+    }
+    let mut edges: Vec<BoundaryEdge> = Vec::new();
+    for (set, contours) in sets.iter().enumerate() {
+        for contour in contours {
+            for i in 0..contour.points.len() {
+                let mut run: Option<BoundaryEdge> = None;
+                for (a, b) in unit_edges(&contour.points, i) {
+                    let left = owners.get(&(b, a)).copied().filter(|&other| other != set);
+                    let kept = !merge || left.map_or(true, |other| set < other);
+                    match run {
+                        Some(ref mut edge) if edge.end == a && edge.left == left && kept => edge.end = b,
+                        _ => {
+                            edges.extend(run.take());
+                            if kept {
+                                run = Some(BoundaryEdge { start: a, end: b, right: set, left });
+                            }
+                        }
+                    }
+                }
+                edges.extend(run);
+            }
+        }
+    }
+    edges
+}
+
+/*
+ Build the outline/hole hierarchy of contours given in scan order:
+ the parent of a contour is the smallest contour of the opposite kind around its top left pixel, the parents being traced before their children
+*/
+#[cfg(feature = "array")]
+pub(crate) fn hierarchy(contours: Vec<Contour>) -> Vec<OutlineNode> {
+    let parents = parents(&contours);
+    hierarchy_with(contours, &parents)
+}
+
+/*
+ Build the outline/hole hierarchy of contours given in scan order with the index of the parent of each contour
+*/
+pub(crate) fn hierarchy_with(contours: Vec<Contour>, parents: &[Option<usize>]) -> Vec<OutlineNode> {
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); contours.len()];
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, &parent) in parents.iter().enumerate() {
+        match parent {
+            Some(j) => children[j].push(i),
+            None => roots.push(i),
+        }
+    }
+    let mut id: usize = 0;
+    let mut ids: Vec<usize> = vec![0; contours.len()];
+    for (i, contour) in contours.iter().enumerate() {
+        if !contour.is_hole {
+            ids[i] = id;
+            id += 1;
+        }
+    }
+    let mut contours: Vec<Option<Contour>> = contours.into_iter().map(Some).collect();
+    roots.iter().map(|&i| outline_node(i, &mut contours, &children, &ids)).collect()
+}
+
+/*
+ The index of the parent of each contour (the smallest contour of the opposite kind around it) in a vec of contours in scan order
+*/
+#[cfg(feature = "array")]
+pub(crate) fn parents(contours: &[Contour]) -> Vec<Option<usize>> {
+    nest(contours, None, false).1
+}
+
+/*
+ The parent of each contour (the smallest contour of the opposite kind and of the same label around its top left pixel),
+ found by a sweep of the rows which crosses the vertical edges of the contours from left to right, in O(area + perimeter):
+ the contours are visited by their top left vertices, and an edge either enters the contour which crosses it or goes back
+ to the contour around this one. A contour only nests in the contours of its own label (the label of the foreground
+ on the right-hand side of its edges), so a vertical edge has a contour for the label on each side.
+ With row_major, the contours which the row-major scan does not trace are skipped (not kept, without a parent):
+ an outline inside an outline (e.g. a cell between holes touching by their corners) and a hole which is not inside an outline
+*/
+pub(crate) fn nest(contours: &[Contour], labels: Option<&[u8]>, row_major: bool) -> (Vec<bool>, Vec<Option<usize>>) {
+    let label = |i: usize| labels.map_or(0, |labels| labels[i] as usize);
+    let mut kept = vec![false; contours.len()];
+    let mut around: Vec<Option<usize>> = vec![None; contours.len()]; // The innermost contour around each contour
+    let mut parents: Vec<Option<usize>> = vec![None; contours.len()];
+    let top_left = |c: &Contour| c.points.iter().copied().min_by_key(|p| (p.1, p.0));
+    let mut order: Vec<(usize, (i32, i32))> = contours.iter().enumerate().filter_map(|(i, c)| top_left(c).map(|p| (i, p))).collect();
+    order.sort_by_key(|&(i, p)| (p.1, p.0, i));
+    let (mut x0, mut y0, mut x1, mut y1) = (i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+    for p in contours.iter().flat_map(|c| c.points.iter()) {
+        x0 = x0.min(p.0); y0 = y0.min(p.1); x1 = x1.max(p.0); y1 = y1.max(p.1);
+    }
+    if order.is_empty() {
+        return (kept, parents);
+    }
+    let width = (x1 - x0 + 1) as usize;
+    // The last contour crossing each vertical edge downwards and upwards (its index plus 1, shifted), and if it is around the right-hand side of the edge
+    let mut edges = vec![0u32; (y1 - y0) as usize * width * 2];
+    let mut current: Vec<Option<usize>> = vec![None; labels.map_or(1, |_| 256)];
+    let mut next: usize = 0;
+    for y in y0..y1 {
+        current.iter_mut().for_each(|c| *c = None);
+        for x in x0..=x1 {
+            let slot = ((y - y0) as usize * width + (x - x0) as usize) * 2;
+            for &edge in &edges[slot..slot + 2] {
+                if edge != 0 {
+                    let i = (edge >> 1) as usize - 1;
+                    current[label(i)] = if edge & 1 == 1 { Some(i) } else { around[i] };
+                }
+            }
+            while next < order.len() && order[next].1 == (x, y) {
+                let i = order[next].0;
+                let is_hole = contours[i].is_hole;
+                let enclosing = current[label(i)];
+                if !row_major || is_hole == enclosing.map_or(false, |p| !contours[p].is_hole) {
+                    kept[i] = true;
+                    around[i] = enclosing;
+                    parents[i] = match enclosing {
+                        Some(p) if contours[p].is_hole == is_hole => parents[p], // Some contours were filtered out
+                        _ => enclosing,
+                    };
+                    let points = &contours[i].points;
+                    for (k, &a) in points.iter().enumerate() {
+                        let b = points[(k + 1) % points.len()];
+                        if a.0 == b.0 {
+                            // Going up, the foreground is on the right of the edge and an outline is around its right-hand side
+                            let up = b.1 < a.1;
+                            let edge = ((i as u32 + 1) << 1) | (up != is_hole) as u32;
+                            for row in a.1.min(b.1)..a.1.max(b.1) {
+                                edges[((row - y0) as usize * width + (a.0 - x0) as usize) * 2 + up as usize] = edge;
+                            }
+                        }
+                    }
+                    current[label(i)] = Some(i); // The edge of its top left vertex enters it
+                }
+                next += 1;
+            }
+        }
+    }
+    (kept, parents)
+}
+
+fn outline_node(i: usize, contours: &mut [Option<Contour>], children: &[Vec<usize>], ids: &[usize]) -> OutlineNode {
+    let outline = contours[i].take().unwrap_or_default();
+    let holes = children[i].iter().map(|&h| HoleNode {
+        hole: contours[h].take().unwrap_or_default(),
+        outlines: children[h].iter().map(|&o| outline_node(o, contours, children, ids)).collect(),
+    }).collect();
+    OutlineNode { id: ids[i], outline, holes }
+}
+
+fn distance2(a: (i32, i32), b: (i32, i32)) -> i64 {
+    let (dx, dy) = ((b.0 - a.0) as i64, (b.1 - a.1) as i64);
+    dx * dx + dy * dy
+}
+
+/*
+ Distance from p to the segment line (a, b)
+*/
+fn distance(a: (i32, i32), b: (i32, i32), p: (i32, i32)) -> f64 {
+    let length = (distance2(a, b) as f64).sqrt();
+    if length == 0.0 { (distance2(a, p) as f64).sqrt() }
+    else { (((b.0 - a.0) as i64 * (a.1 - p.1) as i64 - (a.0 - p.0) as i64 * (b.1 - a.1) as i64) as f64).abs() / length }
+}
+
+/*
+ Ramer–Douglas–Peucker on the polyline points[first..=last], marking the kept vertices
+*/
+fn rdp(points: &[(i32, i32)], first: usize, last: usize, epsilon: f64, keep: &mut [bool]) {
+    if last <= first + 1 {
+        return;
+    }
+    let mut max_distance = 0.0;
+    let mut index = first;
+    for (i, &p) in points.iter().enumerate().take(last).skip(first + 1) {
+        let distance = distance(points[first], points[last], p);
+        if distance > max_distance {
+            max_distance = distance;
+            index = i;
+        }
+    }
+    if max_distance > epsilon {
+        keep[index] = true;
+        rdp(points, first, index, epsilon, keep);
+        rdp(points, index, last, epsilon, keep);
+    }
+}
+
+/*
+ Offset the region enclosed by a ring by delta pixels (a dilation or an erosion by a square of 2 * delta + 1 pixels),
+ returning the traced contours of the offset region with the direction of the ring (an offset hole is made of holes)
+*/
+pub(crate) fn offset_ring(points: &[(i32, i32)], is_hole: bool, delta: i32) -> Vec<Contour> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let margin = delta.max(0);
+    let x0 = points.iter().map(|p| p.0).min().unwrap_or(0) - margin;
+    let y0 = points.iter().map(|p| p.1).min().unwrap_or(0) - margin;
+    let width = (points.iter().map(|p| p.0).max().unwrap_or(0) + margin - x0) as usize;
+    let height = (points.iter().map(|p| p.1).max().unwrap_or(0) + margin - y0) as usize;
+    let mut mask = vec![vec![false; width]; height];
+    fill_ring(points, &mut mask, (x0, y0));
+    let radius = delta.unsigned_abs() as usize;
+    for row in mask.iter_mut() {
+        *row = morph_line(row, radius, delta > 0);
+    }
+    for c in 0..width {
+        let column = morph_line(&mask.iter().map(|row| row[c]).collect::<Vec<bool>>(), radius, delta > 0);
+        for (row, value) in mask.iter_mut().zip(column) {
+            row[c] = value;
+        }
+    }
+    let mut contours = super::grid::bordered(height, width, |r, c| mask[r][c]);
+    let mut offset = Vec::new();
+    super::grid::scan(&mut contours, |outline, traced| {
+        let mut ring: Vec<(i32, i32)> = traced.iter().map(|p| (p.0 + x0, p.1 + y0)).collect();
+        if is_hole {
+            ring[1..].reverse(); // Keep the top left vertex first
+        }
+        offset.push(Contour::new(ring, outline == is_hole));
+    });
+    offset
+}
+
+/*
+ Fill the pixels enclosed by a ring into a mask whose top left pixel is at origin (even-odd rule)
+*/
+pub(crate) fn fill_ring(points: &[(i32, i32)], mask: &mut [Vec<bool>], origin: (i32, i32)) {
+    let mut crossings: Vec<Vec<i32>> = vec![Vec::new(); mask.len()];
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        if a.0 == b.0 {
+            for y in a.1.min(b.1)..a.1.max(b.1) {
+                crossings[(y - origin.1) as usize].push(a.0 - origin.0);
+            }
+        }
+    }
+    for (row, xs) in mask.iter_mut().zip(crossings.iter_mut()) {
+        xs.sort_unstable();
+        for pair in xs.chunks(2) {
+            if let [start, end] = *pair {
+                for cell in &mut row[start as usize..end as usize] {
+                    *cell = !*cell;
+                }
+            }
+        }
+    }
+}
+
+/*
+ Dilate (or erode) a line of pixels by radius pixels on each side, the pixels outside the line are not set
+*/
+fn morph_line(line: &[bool], radius: usize, dilate: bool) -> Vec<bool> {
+    let mut sums = vec![0usize; line.len() + 1];
+    for (i, &value) in line.iter().enumerate() {
+        sums[i + 1] = sums[i] + value as usize;
+    }
+    (0..line.len()).map(|i| {
+        let (start, end) = (i.saturating_sub(radius), (i + radius + 1).min(line.len()));
+        if dilate { sums[end] > sums[start] } else { i >= radius && i + radius < line.len() && sums[end] - sums[start] == end - start }
+    }).collect()
+}
+
+/*
+ Reuse the contour at index n of a caller-provided vec (or push a new one),
+ keeping the allocation of its points when possible
+*/
+#[cfg(feature = "array")]
+pub(crate) fn reuse_contour(out: &mut Vec<Contour>, n: usize, points: &[(i32, i32)], is_hole: bool) {
+    if n < out.len() {
+        out[n].points.clear();
+        out[n].points.extend_from_slice(points);
+        out[n].is_hole = is_hole;
+        out[n].explicitly_closed = false;
+    }
+    else {
+        out.push(Contour::new(points.to_vec(), is_hole));
+    }
+}
+
+/*
+ A number rounded to some decimals without the trailing zeros (and without a negative zero)
+*/
+pub(crate) fn number(value: f64, precision: u8) -> String {
+    let value = format!("{:.*}", precision as usize, value);
+    let value = if precision > 0 { value.trim_end_matches('0').trim_end_matches('.') } else { &value };
+    if value == "-0" { "0".to_string() } else { value.to_string() }
+}
+
+/*
+ Append a contour to a string of SVG Path commands
+*/
+pub(crate) fn push_path(paths: &mut String, points: &[(i32, i32)], closepaths: bool) {
+    let mut previous = points[0];
+    paths.push_str(&format!("M{} {}", previous.0, previous.1));
+    for &point in &points[1..] {
+        if point.1 == previous.1 { paths.push_str(&format!("H{}", point.0)); } else { paths.push_str(&format!("V{}", point.1)); }
+        previous = point;
+    }
+    if closepaths { paths.push('Z'); }
+}
+
+/// Return the signed area of a ring (shoelace formula), see [`Contour::signed_area`].
+pub(crate) fn ring_signed_area(points: &[(i32, i32)]) -> i64 {
+    ring_signed_area_x2(points) / 2
+}
+
+// Twice the signed area of a ring (exact, even for the rings which are not rectilinear)
+fn ring_signed_area_x2(points: &[(i32, i32)]) -> i64 {
+    let n = points.len();
+    (0..n).map(|i| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64
+    }).sum::<i64>()
+}
+
+/// Return the open runs of a contour without its edges on the sides `x0`, `y0`, `x1` and `y1` of the frame,
+/// or `None` if the contour has no edge on the sides.
+pub(crate) fn frame_runs(ring: &[(i32, i32)], (x0, y0): (i32, i32), (x1, y1): (i32, i32)) -> Option<Vec<Vec<(i32, i32)>>> {
+    let n = ring.len();
+    let pieces: Vec<((i32, i32), (i32, i32))> = (0..n).map(|i| (ring[i], ring[(i + 1) % n]))
+        .filter(|(a, b)| if a.0 == b.0 { a.0 != x0 && a.0 != x1 } else { a.1 != y0 && a.1 != y1 })
+        .collect();
+    if pieces.len() == n { None } else { Some(runs(pieces)) }
+}
+
+/// Join the consecutive pieces `(start, end)` of the edges of a contour into runs, the last run going on with the first one if they meet.
+pub(crate) fn runs(pieces: Vec<((i32, i32), (i32, i32))>) -> Vec<Vec<(i32, i32)>> {
+    let mut runs: Vec<Vec<(i32, i32)>> = Vec::new();
+    for (start, end) in pieces {
+        match runs.last_mut() {
+            Some(run) if run.last() == Some(&start) => run.push(end),
+            _ => runs.push(vec![start, end]),
+        }
+    }
+    if runs.len() > 1 && runs[0][0] == runs[runs.len() - 1][runs[runs.len() - 1].len() - 1] { // The last run goes on with the first run
+        let first = runs.remove(0);
+        let last = runs.last_mut().unwrap();
+        last.extend_from_slice(&first[1..]);
+    }
+    runs
+}
+
+/// Same as [`push_path`] but with the relative SVG Path commands, `current` is the current point of the previous path
+/// (the first point of a closed path or the last point of an open path) and is updated.
+#[cfg(feature = "array")]
+pub(crate) fn push_relative_path(paths: &mut String, points: &[(i32, i32)], closepaths: bool, current: &mut (i32, i32)) {
+    let mut previous = points[0];
+    paths.push_str(&format!("m{} {}", previous.0 - current.0, previous.1 - current.1));
+    for &point in &points[1..] {
+        if point.1 == previous.1 { paths.push_str(&format!("h{}", point.0 - previous.0)); } else { paths.push_str(&format!("v{}", point.1 - previous.1)); }
+        previous = point;
+    }
+    if closepaths { paths.push('z'); }
+    *current = if closepaths { points[0] } else { previous };
+}
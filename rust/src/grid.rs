@@ -0,0 +1,659 @@
+/*
+ * Contour tracing library
+ * https://github.com/STPR/contour_tracing
+ *
+ * Copyright (c) 2022, STPR - https://github.com/STPR
+ *
+ * SPDX-License-Identifier: EUPL-1.2
+ */
+
+pub(crate) const O_VERTEX_WITH_BORDER: [(i8, i8); 7] = [(-1, 0), (0, 0), (-1, -1), (0, 0), (0, -1), (0, 0), (0, 0)]; // Bottom left coordinates with a border
+pub(crate) const H_VERTEX_WITH_BORDER: [(i8, i8); 7] = [(0, 0), (0, 0), (-1, 0), (0, 0), (-1, -1), (0, 0), (0, -1)]; // Bottom right coordinates with a border
+const O_VALUE_FOR_SIGNED:   [i8; 7]       = [1, 0, 2, 0, 4, 0, 8];     // Value to add into an array of contours (using signed integers)
+const H_VALUE_FOR_SIGNED:   [i8; 7]       = [-4, 0, -8, 0, -1, 0, -2]; // (idem)
+
+/*
+ Scan an array of contours (with a border) and call f(outline, vertices) for each traced contour
+*/
+pub(crate) fn scan<F: FnMut(bool, &[(i32, i32)])>(contours: &mut [Vec<i8>], mut f: F) {
+    scan_while(contours, |outline, points| {
+        f(outline, points);
+        true
+    });
+}
+
+/*
+ Same as scan but the scan stops as soon as f returns false
+*/
+pub(crate) fn scan_while<F: FnMut(bool, &[(i32, i32)]) -> bool>(contours: &mut [Vec<i8>], mut f: F) {
+    let mut state = ScanState::new();
+    let mut points: Vec<(i32, i32)> = Vec::new();
+    while let Some(outline) = state.next(contours, &mut points) {
+        if !f(outline, &points) { return; }
+    }
+}
+
+/*
+ The position of a row-major scan (and its outline/hole levels) which can be resumed after each traced contour
+*/
+#[derive(Clone, Debug)]
+pub(crate) struct ScanState {
+    cursor_x: usize,
+    cursor_y: usize,
+    ol: usize,
+    hl: usize,
+    depth: usize,
+}
+
+impl ScanState {
+    pub(crate) fn new() -> Self {
+        ScanState { cursor_x: 1, cursor_y: 1, ol: 0, hl: 0, depth: 0 }
+    }
+
+    /*
+     The nesting depth of the last traced contour: 1 for an outline which is not in a hole, 2 for its holes, and so on
+    */
+    #[cfg(feature = "array")]
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /*
+     Trace the next contour into points and return Some(outline), or None at the end of the scan
+    */
+    pub(crate) fn next(&mut self, contours: &mut [Vec<i8>], points: &mut Vec<(i32, i32)>) -> Option<bool> {
+        let rows: usize = contours.len() - 2;
+        let cols: usize = contours[0].len() - 2;
+        while self.cursor_y <= rows {
+            while self.cursor_x <= cols {
+                let (cursor_x, cursor_y) = (self.cursor_x, self.cursor_y);
+                let mut traced: Option<bool> = None;
+                self.depth = self.ol + self.hl + 1; // The levels count the enclosing contours
+                if self.ol == self.hl && contours[cursor_y][cursor_x] == 1 {
+                    trace_bits(true, cursor_x, cursor_y, [2, 3, 4, 5, 6, 7, 0, 1], 2, (7, 1, 0), O_VERTEX_WITH_BORDER, O_VALUE_FOR_SIGNED, contours, points);
+                    traced = Some(true);
+                }
+                else if self.ol > self.hl && contours[cursor_y][cursor_x] == -1 {
+                    trace_bits(false, cursor_x, cursor_y, [4, 5, 6, 7, 0, 1, 2, 3], -2, (1, 7, 6), H_VERTEX_WITH_BORDER, H_VALUE_FOR_SIGNED, contours, points);
+                    traced = Some(false);
+                }
+                match contours[cursor_y][cursor_x].abs() {
+                    2 |   4 |  10 |  12 => if contours[cursor_y][cursor_x] > 0 { self.ol += 1 } else { self.hl += 1 },
+                    5 |   7 |  13 |  15 => if contours[cursor_y][cursor_x] > 0 { self.ol -= 1 } else { self.hl -= 1 },
+                    _ => ()
+                }
+                self.cursor_x += 1;
+                if let Some(outline) = traced {
+                    // The classification by the levels must agree with the direction of the contour
+                    debug_assert!((super::contour::ring_signed_area(points) > 0) == outline, "misclassified contour at {:?}", points[0]);
+                    return traced;
+                }
+            }
+            self.cursor_x = 1;
+            self.cursor_y += 1;
+            self.ol = 0;
+            self.hl = 0;
+        }
+        None
+    }
+}
+
+/*
+ Scan an array of contours (with a border) by tiles in Morton order and call f(outline, depth, vertices) for each traced contour
+
+ The Pavlidis' tracer relies on the row-major scan (ol/hl levels), so the contours are followed here
+ along the cracks between the cells, keeping the foreground on the right-hand side.
+ Where two cells touch only by a corner, the outlines keep the foreground cells apart (turn right)
+ and the holes keep the background cells apart (turn left), as the Pavlidis' tracer does.
+ Each horizontal crack is visited once by each rule, the clockwise contours of the outline rule are the outlines and the
+ counterclockwise contours of the hole rule are the holes. The contours are sorted back into the row-major order (the first vertex
+ of a contour is its top left vertex) and the ones which the row-major scan does not trace are skipped, so the contours and
+ their depths (as in ScanState) are exactly the ones of the row-major scan.
+*/
+#[cfg(feature = "array")]
+pub(crate) fn scan_morton<F: FnMut(bool, usize, &[(i32, i32)])>(contours: &[Vec<i8>], size: usize, f: &mut F) {
+    scan_cracks(contours, size, false, f);
+}
+
+/*
+ Scan an array of contours (with a border) with 8-connected foreground cells and call f(outline, depth, vertices) for each traced contour
+
+ Where two foreground cells touch only by a corner, the contours always turn left to keep them together,
+ so a contour going through such a corner twice has the vertex twice. The contours are in row-major order.
+*/
+#[cfg(feature = "array")]
+pub(crate) fn scan_eight<F: FnMut(bool, usize, &[(i32, i32)])>(contours: &[Vec<i8>], f: &mut F) {
+    scan_cracks(contours, contours.len().max(contours[0].len()), true, f);
+}
+
+#[cfg(feature = "array")]
+fn scan_cracks<F: FnMut(bool, usize, &[(i32, i32)])>(contours: &[Vec<i8>], size: usize, eight: bool, f: &mut F) {
+    let rows: usize = contours.len() - 2;
+    let cols: usize = contours[0].len() - 2;
+    let size = size.max(1);
+    let mut tiles: Vec<(usize, usize)> = Vec::new();
+    for ty in 0..(rows + size - 1) / size {
+        for tx in 0..(cols + size - 1) / size {
+            tiles.push((tx, ty));
+        }
+    }
+    tiles.sort_by_key(|&(tx, ty)| morton_code(tx, ty));
+    // The horizontal cracks visited by the contours kept apart by the outline rule and by the hole rule: with 4-connected cells,
+    // each crack is on an outline or the inner boundary of a shape (outline rule), and on a hole or the outer boundary of a region (hole rule)
+    let mut visited = [vec![false; (rows + 1) * cols], vec![false; (rows + 1) * cols]];
+    let rules: &[bool] = if eight { &[true] } else { &[false, true] };
+    let mut traced: Vec<(bool, Vec<(i32, i32)>)> = Vec::new();
+    for (tx, ty) in tiles {
+        for cursor_y in ty * size + 1..=((ty + 1) * size).min(rows) {
+            for cursor_x in tx * size + 1..=((tx + 1) * size).min(cols) {
+                let fg = contours[cursor_y][cursor_x] > 0;
+                if fg == (contours[cursor_y - 1][cursor_x] > 0) {
+                    continue;
+                }
+                for &hole in rules {
+                    let visited = &mut visited[hole as usize];
+                    if visited[(cursor_y - 1) * cols + cursor_x - 1] {
+                        continue;
+                    }
+                    let start = if fg { (cursor_x as i32 - 1, cursor_y as i32 - 1, 1, 0) } else { (cursor_x as i32, cursor_y as i32 - 1, -1, 0) };
+                    let points = trace_cracks(start, |x, y| contours[(y + 1) as usize][(x + 1) as usize] > 0, hole);
+                    for segment in points.windows(2).chain(std::iter::once([points[points.len() - 1], points[0]].as_slice())) {
+                        if segment[0].1 == segment[1].1 {
+                            for x in segment[0].0.min(segment[1].0)..segment[0].0.max(segment[1].0) {
+                                visited[segment[0].1 as usize * cols + x as usize] = true;
+                            }
+                        }
+                    }
+                    let outline = if eight { super::contour::ring_signed_area(&points) > 0 } else { points[1].1 == points[0].1 };
+                    if eight || outline != hole {
+                        traced.push((outline, points));
+                    }
+                }
+            }
+        }
+    }
+    traced.sort_by_key(|(_, points)| (points[0].1, points[0].0));
+    // With 4-connected cells, the outline and the hole of a crack can both be traced: as in the row-major scan, an outline
+    // is a contour where there is no outline around it and a hole where there is, its depth counts the contours around it
+    let traced: Vec<super::contour::Contour> = traced.into_iter().map(|(outline, points)| super::contour::Contour::new(points, !outline)).collect();
+    let (kept, parents) = super::contour::nest(&traced, None, !eight);
+    let mut depths: Vec<usize> = vec![0; traced.len()];
+    for (i, contour) in traced.iter().enumerate() {
+        if kept[i] {
+            depths[i] = parents[i].map_or(1, |p| depths[p] + 1);
+            f(!contour.is_hole, depths[i], &contour.points);
+        }
+    }
+}
+
+/*
+ Follow the cracks from a starting vertex and direction (x, y, dx, dy), keeping the foreground (fg(x, y) for a cell)
+ on the right-hand side, and return the vertices with the top left vertex first
+*/
+fn trace_cracks<G: Fn(i32, i32) -> bool>(start: (i32, i32, i32, i32), fg: G, hole: bool) -> Vec<(i32, i32)> {
+    let (mut x, mut y, mut dx, mut dy) = start;
+    let mut points: Vec<(i32, i32)> = Vec::new();
+    loop {
+        x += dx;
+        y += dy;
+        let (rx, ry) = (-dy, dx); // Right-hand side
+        let ahead_right = fg(x + (dx + rx - 1) / 2, y + (dy + ry - 1) / 2);
+        let ahead_left  = fg(x + (dx - rx - 1) / 2, y + (dy - ry - 1) / 2);
+        let (nx, ny) =
+            if ahead_left && (ahead_right || hole) { (-rx, -ry) }
+            else if !ahead_right                   { (rx, ry) }
+            else { (dx, dy) };
+        if (nx, ny) != (dx, dy) {
+            points.push((x, y));
+        }
+        dx = nx;
+        dy = ny;
+        if (x, y, dx, dy) == start {
+            break;
+        }
+    }
+    let first = (0..points.len()).min_by_key(|&i| (points[i].1, points[i].0)).unwrap_or(0);
+    points.rotate_left(first);
+    points
+}
+
+/*
+ Trace the contours of all the labels of a grid (with a border of None) in a single scan and return each contour, its label and
+ the index of its parent (the contours of a label nest in the contours of the same label), in row-major order
+
+ As in scan_cracks, the contours are followed along the cracks, with the cells of their label on the right-hand side:
+ a horizontal crack is on a contour (of each rule) of the label below and on a contour of the label above,
+ the contours which the row-major scan of each label does not trace are skipped.
+*/
+#[cfg(feature = "image")]
+type LabelCandidate = (u8, bool, Vec<(i32, i32)>); // The label, outline and vertices of a traced contour
+
+#[cfg(feature = "image")]
+pub(crate) fn scan_labels(labels: &[Vec<Option<u8>>]) -> Vec<(u8, super::contour::Contour, Option<usize>)> {
+    let rows: usize = labels.len() - 2;
+    let cols: usize = labels[0].len() - 2;
+    let mut visited = vec![[[false; 2]; 2]; (rows + 1) * cols]; // Horizontal cracks, by side (label below, label above) and rule
+    let mut traced: Vec<LabelCandidate> = Vec::new();
+    for cursor_y in 1..=rows {
+        for cursor_x in 1..=cols {
+            let crack = (cursor_y - 1) * cols + cursor_x - 1;
+            let (below, above) = (labels[cursor_y][cursor_x], labels[cursor_y - 1][cursor_x]);
+            if below == above {
+                continue;
+            }
+            for (side, label) in [below, above].iter().enumerate() {
+                let label = match *label {
+                    Some(label) => label,
+                    None => continue,
+                };
+                for hole in [false, true].iter().copied() {
+                    if visited[crack][side][hole as usize] {
+                        continue;
+                    }
+                    let start = if side == 0 { (cursor_x as i32 - 1, cursor_y as i32 - 1, 1, 0) } else { (cursor_x as i32, cursor_y as i32 - 1, -1, 0) };
+                    let points = trace_cracks(start, |x, y| labels[(y + 1) as usize][(x + 1) as usize] == Some(label), hole);
+                    for segment in points.windows(2).chain(std::iter::once([points[points.len() - 1], points[0]].as_slice())) {
+                        if segment[0].1 == segment[1].1 {
+                            let side = (segment[1].0 < segment[0].0) as usize; // Going east, the label is below
+                            for x in segment[0].0.min(segment[1].0)..segment[0].0.max(segment[1].0) {
+                                visited[segment[0].1 as usize * cols + x as usize][side][hole as usize] = true;
+                            }
+                        }
+                    }
+                    let outline = points[1].1 == points[0].1;
+                    if outline != hole {
+                        traced.push((label, outline, points));
+                    }
+                }
+            }
+        }
+    }
+    traced.sort_by_key(|(_, _, points)| (points[0].1, points[0].0));
+    let (traced_labels, traced): (Vec<u8>, Vec<super::contour::Contour>) = traced.into_iter().map(|(label, outline, points)| (label, super::contour::Contour::new(points, !outline))).unzip();
+    let (kept, parents) = super::contour::nest(&traced, Some(&traced_labels), true);
+    let mut ids: Vec<usize> = vec![0; traced.len()];
+    let mut scanned: Vec<(u8, super::contour::Contour, Option<usize>)> = Vec::new();
+    for (i, contour) in traced.into_iter().enumerate() {
+        if kept[i] {
+            ids[i] = scanned.len();
+            scanned.push((traced_labels[i], contour, parents[i].map(|p| ids[p])));
+        }
+    }
+    scanned
+}
+
+#[cfg(feature = "array")]
+fn morton_code(x: usize, y: usize) -> u64 {
+    let mut code: u64 = 0;
+    for bit in 0..32 {
+        code |= ((x as u64 >> bit) & 1) << (2 * bit) | ((y as u64 >> bit) & 1) << (2 * bit + 1);
+    }
+    code
+}
+
+/*
+ Trace the contours of a stream of rows (one row of pixels at a time) and keep only the previous row and the open contours
+
+ The contours are followed along the cracks between the pixels, keeping the foreground on the right-hand side:
+ at each vertex of a row, the cracks arriving at the vertex are linked to the cracks leaving it,
+ and the open contours are joined until they close. Where two pixels touch only by a corner,
+ the foreground pixels are kept apart (turn right), as the outlines of the Pavlidis' tracer do,
+ then the holes are split where they touch themselves, to keep the background pixels apart as the holes of the Pavlidis' tracer do.
+ The closed contours are sorted back into the row-major order: the first vertex of a contour is its top left vertex.
+*/
+#[cfg(feature = "image")]
+pub(crate) struct RowTracer {
+    y: i32,
+    previous: Vec<bool>,
+    vertical: Vec<Option<usize>>, // The open contour of the vertical crack above each vertex of the row
+    horizontal: Option<usize>,    // The open contour of the horizontal crack on the left of the vertex
+    chains: Vec<Option<Chain>>,
+    free: Vec<usize>,
+    closed: Vec<Vec<(i32, i32)>>,
+    corners: Vec<(i32, i32)>, // The vertices where two pixels touch only by a corner
+}
+
+#[cfg(feature = "image")]
+struct Chain {
+    points: std::collections::VecDeque<(i32, i32)>,
+    front: Slot, // The open crack arriving at the first vertex
+    back: Slot,  // The open crack leaving the last vertex
+}
+
+#[cfg(feature = "image")]
+#[derive(Clone, Copy)]
+enum Slot {
+    Vertical(usize),
+    Horizontal,
+}
+
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Crack {
+    North,
+    South,
+    West,
+    East,
+}
+
+#[cfg(feature = "image")]
+impl RowTracer {
+    pub(crate) fn new(width: usize) -> Self {
+        RowTracer { y: 0, previous: vec![false; width], vertical: vec![None; width + 1], horizontal: None, chains: Vec::new(), free: Vec::new(), closed: Vec::new(), corners: Vec::new() }
+    }
+
+    /*
+     Link the cracks between the previous row and this row (true for the foreground)
+    */
+    pub(crate) fn push_row(&mut self, row: &[bool]) {
+        let width = self.previous.len();
+        for x in 0..=width {
+            let cell = |r: &[bool], x: usize| x < width && r[x];
+            let (tl, tr) = (x > 0 && self.previous[x - 1], cell(&self.previous, x));
+            let (bl, br) = (x > 0 && row[x - 1], cell(row, x));
+            let v = (x as i32, self.y);
+            let north = if tl != tr { Some(self.vertical[x].take()) } else { None };
+            let west = if tl != bl { Some(self.horizontal.take()) } else { None };
+            match (north, west, bl != br, tr != br) {
+                (None, None, false, false) => (),
+                (Some(n), Some(w), true, true) => {
+                    // Two pixels touch only by a corner: the crack arriving at the vertex turns right
+                    self.corners.push(v);
+                    if tl {
+                        self.link(v, x, (Crack::North, n), (Crack::West, w));
+                        self.link(v, x, (Crack::South, None), (Crack::East, None));
+                    }
+                    else {
+                        self.link(v, x, (Crack::East, None), (Crack::North, n));
+                        self.link(v, x, (Crack::West, w), (Crack::South, None));
+                    }
+                }
+                (north, west, south, east) => {
+                    let mut cracks = Vec::with_capacity(2);
+                    if let Some(n) = north { cracks.push((Crack::North, n, tl)); }
+                    if let Some(w) = west { cracks.push((Crack::West, w, bl)); }
+                    if south { cracks.push((Crack::South, None, !bl)); }
+                    if east { cracks.push((Crack::East, None, !br)); }
+                    let (a, d) = if cracks[0].2 { (cracks[0], cracks[1]) } else { (cracks[1], cracks[0]) };
+                    self.link(v, x, (a.0, a.1), (d.0, d.1));
+                }
+            }
+        }
+        self.previous.copy_from_slice(row);
+        self.y += 1;
+    }
+
+    /*
+     Close the last row and return the contours as (outline, vertices)
+    */
+    pub(crate) fn finish(mut self) -> Vec<(bool, Vec<(i32, i32)>)> {
+        let background = vec![false; self.previous.len()];
+        self.push_row(&background);
+        debug_assert!(self.chains.iter().all(Option::is_none));
+        // The contours are cycles of vertices (next), each vertex where two pixels touch only by a corner is visited twice
+        let mut point: Vec<(i32, i32)> = Vec::new();
+        let mut next: Vec<usize> = Vec::new();
+        for ring in &self.closed {
+            let start = point.len();
+            point.extend_from_slice(ring);
+            next.extend((1..ring.len()).map(|i| start + i).chain(std::iter::once(start)));
+        }
+        let corners: std::collections::HashSet<(i32, i32)> = self.corners.into_iter().collect();
+        let mut visits: std::collections::HashMap<(i32, i32), Vec<usize>> = std::collections::HashMap::new();
+        for (i, p) in point.iter().enumerate().filter(|(_, p)| corners.contains(p)) {
+            visits.entry(*p).or_default().push(i);
+        }
+        let mut pairs: Vec<(usize, usize)> = visits.values().map(|v| (v[0], v[1])).collect();
+        // A hole keeps the background pixels apart instead, as the holes of the Pavlidis' tracer do:
+        // swapping the next vertices of the two visits joins the contours of the vertex or splits its contour
+        loop {
+            let (cycle, areas) = cycles(&point, &next);
+            let n = pairs.len();
+            pairs.retain(|&(p, q)| {
+                let hole = areas[cycle[p]] < 0 || areas[cycle[q]] < 0;
+                if hole { next.swap(p, q); }
+                !hole
+            });
+            if pairs.len() == n { break; }
+        }
+        let (cycle, areas) = cycles(&point, &next);
+        let mut contours: Vec<(bool, Vec<(i32, i32)>)> = areas.iter().map(|&area| (area > 0, Vec::new())).collect();
+        let mut visited = vec![false; point.len()];
+        for start in 0..point.len() {
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                contours[cycle[i]].1.push(point[i]);
+                i = next[i];
+            }
+        }
+        for (_, points) in contours.iter_mut() {
+            let first = (0..points.len()).min_by_key(|&i| (points[i].1, points[i].0)).unwrap_or(0);
+            points.rotate_left(first);
+        }
+        contours.sort_by_key(|(_, points)| (points[0].1, points[0].0));
+        contours
+    }
+
+    /*
+     Link the crack arriving at the vertex v to the crack leaving it, with the open contour of each crack (None for a new crack)
+    */
+    fn link(&mut self, v: (i32, i32), x: usize, (arriving, a): (Crack, Option<usize>), (leaving, d): (Crack, Option<usize>)) {
+        let turn = !matches!((arriving, leaving), (Crack::North, Crack::South) | (Crack::South, Crack::North) | (Crack::West, Crack::East) | (Crack::East, Crack::West));
+        match (a, d) {
+            (Some(a), Some(d)) if a == d => {
+                let mut chain = self.chains[a].take().unwrap();
+                if turn { chain.points.push_back(v); }
+                self.free.push(a);
+                self.closed.push(chain.points.into_iter().collect());
+            }
+            (Some(a), Some(d)) => {
+                let mut head = self.chains[a].take().unwrap();
+                let mut tail = self.chains[d].take().unwrap();
+                if turn { head.points.push_back(v); }
+                // Keep the longest contour and move the other one into it
+                let (id, free, chain) = if head.points.len() >= tail.points.len() {
+                    head.points.append(&mut tail.points);
+                    head.back = tail.back;
+                    (a, d, head)
+                }
+                else {
+                    for &p in head.points.iter().rev() {
+                        tail.points.push_front(p);
+                    }
+                    tail.front = head.front;
+                    (d, a, tail)
+                };
+                let slot = if id == a { chain.back } else { chain.front };
+                self.chains[id] = Some(chain);
+                self.free.push(free);
+                self.place(slot, id);
+            }
+            (Some(a), None) => {
+                let chain = self.chains[a].as_mut().unwrap();
+                if turn { chain.points.push_back(v); }
+                chain.back = slot(leaving, x);
+                self.place(slot(leaving, x), a);
+            }
+            (None, Some(d)) => {
+                let chain = self.chains[d].as_mut().unwrap();
+                if turn { chain.points.push_front(v); }
+                chain.front = slot(arriving, x);
+                self.place(slot(arriving, x), d);
+            }
+            (None, None) => {
+                let chain = Chain { points: std::iter::once(v).collect(), front: slot(arriving, x), back: slot(leaving, x) };
+                let id = match self.free.pop() {
+                    Some(id) => { self.chains[id] = Some(chain); id }
+                    None => { self.chains.push(Some(chain)); self.chains.len() - 1 }
+                };
+                self.place(slot(arriving, x), id);
+                self.place(slot(leaving, x), id);
+            }
+        }
+    }
+
+    /*
+     Store the open contour of a crack which is still open
+    */
+    fn place(&mut self, slot: Slot, id: usize) {
+        match slot {
+            Slot::Vertical(x) => self.vertical[x] = Some(id),
+            Slot::Horizontal => self.horizontal = Some(id),
+        }
+    }
+}
+
+/*
+ Return the cycle of each vertex and the signed area of each cycle (twice the shoelace formula)
+*/
+#[cfg(feature = "image")]
+fn cycles(point: &[(i32, i32)], next: &[usize]) -> (Vec<usize>, Vec<i64>) {
+    let mut cycle = vec![usize::MAX; point.len()];
+    let mut areas: Vec<i64> = Vec::new();
+    for start in 0..point.len() {
+        if cycle[start] != usize::MAX {
+            continue;
+        }
+        let (mut i, mut area) = (start, 0);
+        while cycle[i] == usize::MAX {
+            cycle[i] = areas.len();
+            let (a, b) = (point[i], point[next[i]]);
+            area += a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64;
+            i = next[i];
+        }
+        areas.push(area);
+    }
+    (cycle, areas)
+}
+
+/*
+ The slot of a new crack (below or on the right of the vertex x)
+*/
+#[cfg(feature = "image")]
+fn slot(crack: Crack, x: usize) -> Slot {
+    if crack == Crack::South { Slot::Vertical(x) } else { Slot::Horizontal }
+}
+
+/*
+ Build an array of contours from a foreground test on (row, column),
+ with a border of 1 bit to prevent out-of-bounds error
+*/
+pub(crate) fn bordered<F: Fn(usize, usize) -> bool>(rows: usize, cols: usize, is_fg: F) -> Vec<Vec<i8>> {
+    let mut contours = vec![vec![0i8; cols + 2]; rows + 2];
+    for (r, row) in contours.iter_mut().skip(1).take(rows).enumerate() {
+        for (c, cell) in row.iter_mut().skip(1).take(cols).enumerate() {
+            *cell = if is_fg(r, c) { 1 } else { -1 };
+        }
+    }
+    contours
+}
+
+/*
+ Same as bordered but the rows are filled in parallel with the rayon feature
+*/
+pub(crate) fn bordered_par<F: Fn(usize, usize) -> bool + Sync>(rows: usize, cols: usize, is_fg: F) -> Vec<Vec<i8>> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        let mut contours = vec![vec![0i8; cols + 2]; rows + 2];
+        contours[1..=rows].par_iter_mut().enumerate().for_each(|(r, row)| {
+            for (c, cell) in row.iter_mut().skip(1).take(cols).enumerate() {
+                *cell = if is_fg(r, c) { 1 } else { -1 };
+            }
+        });
+        contours
+    }
+    #[cfg(not(feature = "rayon"))]
+    bordered(rows, cols, is_fg)
+}
+
+/*
+ One step of the Pavlidis' tracer at (x, y) facing o[0], from its 8 neighbors (in the order of MN): move the tracer and rotate o,
+ calling event(true, x, y, o[0]) where the cell under the tracer is marked and event(false, x, y, o[0]) where a vertex is added
+*/
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn trace_step<F: FnMut(bool, usize, usize, usize)>(outline: bool, neighbors: &[i8; 8], tracer_x: &mut usize, tracer_y: &mut usize, o: &mut [usize; 8], rot: i8, viv: (usize, usize, usize), event: &mut F) {
+    let rn =
+        if outline {
+            if      neighbors[o[7]] > 0 && neighbors[o[0]] > 0 { 1 }
+            else if neighbors[o[0]] > 0                        { 2 }
+            else if neighbors[o[1]] > 0 && neighbors[o[2]] > 0 { 3 }
+            else { 0 }
+        }
+        else if neighbors[o[1]] < 0 && neighbors[o[0]] < 0 { 1 }
+        else if neighbors[o[0]] < 0                        { 2 }
+        else if neighbors[o[7]] < 0 && neighbors[o[6]] < 0 { 3 }
+        else { 0 };
+    match rn {
+        1 => {
+            event(true, *tracer_x, *tracer_y, o[0]);
+            *tracer_x = tracer_x.wrapping_add(super::MN[o[viv.0]].0 as usize);
+            *tracer_y = tracer_y.wrapping_add(super::MN[o[viv.0]].1 as usize);
+            o.rotate_right(rot.rem_euclid(8) as usize); // Rotate 90 degrees, counterclockwise for the outlines (rot = 2) or clockwise for the holes (rot = -2)
+            event(false, *tracer_x, *tracer_y, o[0]);
+        }
+        2 => {
+            event(true, *tracer_x, *tracer_y, o[0]);
+            *tracer_x = tracer_x.wrapping_add(super::MN[o[0]].0 as usize);
+            *tracer_y = tracer_y.wrapping_add(super::MN[o[0]].1 as usize);
+        }
+        3 => {
+            event(true, *tracer_x, *tracer_y, o[0]);
+            o.rotate_left(rot.rem_euclid(8) as usize); // Rotate 90 degrees, clockwise for the outlines (rot = 2) or counterclockwise for the holes (rot = -2)
+            event(true, *tracer_x, *tracer_y, o[0]);
+            event(false, *tracer_x, *tracer_y, o[0]);
+            o.rotate_right(rot.rem_euclid(8) as usize);
+            *tracer_x = tracer_x.wrapping_add(super::MN[o[viv.1]].0 as usize);
+            *tracer_y = tracer_y.wrapping_add(super::MN[o[viv.1]].1 as usize);
+            event(false, *tracer_x, *tracer_y, o[0]);
+        }
+        _ => {
+            event(true, *tracer_x, *tracer_y, o[0]);
+            o.rotate_left(rot.rem_euclid(8) as usize);
+            event(false, *tracer_x, *tracer_y, o[0]);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn trace_bits(outline: bool, cursor_x: usize, cursor_y: usize, mut o: [usize; 8], rot: i8, viv: (usize, usize, usize), vertex: [(i8, i8); 7], value: [i8; 7], contours: &mut [Vec<i8>], points: &mut Vec<(i32, i32)>) {
+    let mut tracer_x = cursor_x;
+    let mut tracer_y = cursor_y;
+    let mut vertices_nbr: usize = 1;
+    points.clear();
+    points.push((tracer_x as i32 + vertex[o[0]].0 as i32, tracer_y as i32 + vertex[o[0]].1 as i32));
+    let mut neighbors: [i8; 8];
+    loop {
+        neighbors = [
+            contours[tracer_y - 1][tracer_x    ],
+            contours[tracer_y - 1][tracer_x + 1],
+            contours[tracer_y    ][tracer_x + 1],
+            contours[tracer_y + 1][tracer_x + 1],
+            contours[tracer_y + 1][tracer_x    ],
+            contours[tracer_y + 1][tracer_x - 1],
+            contours[tracer_y    ][tracer_x - 1],
+            contours[tracer_y - 1][tracer_x - 1]
+        ];
+        trace_step(outline, &neighbors, &mut tracer_x, &mut tracer_y, &mut o, rot, viv, &mut |mark, x, y, d| {
+            if mark {
+                contours[y][x] += value[d];
+            } else {
+                vertices_nbr += 1;
+                points.push((x as i32 + vertex[d].0 as i32, y as i32 + vertex[d].1 as i32));
+            }
+        });
+        if tracer_x == cursor_x && tracer_y == cursor_y && vertices_nbr > 2 {
+            break;
+        }
+    }
+    loop {
+        contours[tracer_y][tracer_x] += value[o[0]];
+        if o[0] == viv.2 {
+            break;
+        }
+        o.rotate_left(rot.rem_euclid(8) as usize);
+        points.push((tracer_x as i32 + vertex[o[0]].0 as i32, tracer_y as i32 + vertex[o[0]].1 as i32));
+    }
+}
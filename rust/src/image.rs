@@ -7,7 +7,11 @@
  * SPDX-License-Identifier: EUPL-1.2
  */
 
-use ::image::{ImageBuffer, Luma};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use ::image::error::{ImageFormatHint, UnsupportedError, UnsupportedErrorKind};
+use ::image::{ColorType, DynamicImage, ImageBuffer, ImageDecoder, ImageError, ImageResult, Luma, Rgb, Rgba, RgbaImage};
+use super::contour::{BBox, Contour, FrameBoundary, OutlineNode};
 
 const O_VERTEX_NO_BORDER:   [(i8, i8); 7] = [(0, 1), (0, 0), (0, 0), (0, 0), (1, 0), (0, 0), (1, 1)]; // Bottom left coordinates without a border
 const H_VERTEX_NO_BORDER:   [(i8, i8); 7] = [(1, 1), (0, 0), (0, 1), (0, 0), (0, 0), (0, 0), (1, 0)]; // Bottom right coordinates without a border
@@ -19,6 +23,72 @@ const L8_DEFAULT_VALUE:        u8 = 32;
 const L8_DEFAULT_SIGNED_VALUE: i8 = L8_DEFAULT_VALUE as i8;
 const L8_H_STARTING_VALUE:     u8 = 33;
 
+/// Per-channel ranges of an RGBA pixel, combined with AND semantics:
+/// a pixel is in the foreground when each of its channels is within its range (inclusive).
+///
+/// By default, each range is `0..=255` (every pixel is in the foreground).
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::image::ChannelThresholds;
+/// // Red channel > 128 and green channel < 64
+/// let thresholds = ChannelThresholds::new().red(129, 255).green(0, 63);
+/// # assert!(thresholds.matches(&image::Rgba([200, 10, 255, 255])));
+/// # assert!(!thresholds.matches(&image::Rgba([200, 64, 0, 255])));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChannelThresholds {
+    /// Minimum and maximum values of the red channel
+    pub red: (u8, u8),
+    /// Minimum and maximum values of the green channel
+    pub green: (u8, u8),
+    /// Minimum and maximum values of the blue channel
+    pub blue: (u8, u8),
+    /// Minimum and maximum values of the alpha channel
+    pub alpha: (u8, u8),
+}
+
+impl Default for ChannelThresholds {
+    fn default() -> Self {
+        ChannelThresholds { red: (0, 255), green: (0, 255), blue: (0, 255), alpha: (0, 255) }
+    }
+}
+
+impl ChannelThresholds {
+    /// Create the default thresholds: each range is `0..=255`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the range of the red channel.
+    pub fn red(mut self, min: u8, max: u8) -> Self {
+        self.red = (min, max);
+        self
+    }
+
+    /// Set the range of the green channel.
+    pub fn green(mut self, min: u8, max: u8) -> Self {
+        self.green = (min, max);
+        self
+    }
+
+    /// Set the range of the blue channel.
+    pub fn blue(mut self, min: u8, max: u8) -> Self {
+        self.blue = (min, max);
+        self
+    }
+
+    /// Set the range of the alpha channel.
+    pub fn alpha(mut self, min: u8, max: u8) -> Self {
+        self.alpha = (min, max);
+        self
+    }
+
+    /// Return `true` if each channel of the pixel is within its range.
+    pub fn matches(&self, pixel: &Rgba<u8>) -> bool {
+        [self.red, self.green, self.blue, self.alpha].iter().zip(pixel.0.iter()).all(|(&(min, max), &value)| min <= value && value <= max)
+    }
+}
+
 /// A function that takes an image buffer, an 8-bit luminance value and an option as input and return a string of SVG Path commands as output.
 /// # Examples
 /// ```ignore
@@ -40,8 +110,37 @@ const L8_H_STARTING_VALUE:     u8 = 33;
 /// println!("{}", single_l8_to_paths(&mut image_buffer, foreground_color, true));
 /// ```
 pub fn single_l8_to_paths(buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, closepaths: bool) -> String {
+    trace_l8(buffer, |p| p == luma[0], closepaths)
+}
+
+/// A function that takes an image buffer, a range of 8-bit luminance values and an option as input and return a string of SVG Path commands as output.
+///
+/// The pixels whose value is within `min..=max` are the foreground, e.g. for anti-aliased or noisy images: with `min == max`,
+/// the paths are the ones of [`single_l8_to_paths`], and with `min > max` there is no foreground (an empty string).
+/// Like [`single_l8_to_paths`], the image buffer is **modified**.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::single_l8_range_to_paths;
+/// let mut image_buffer = GrayImage::new(3, 1);
+///
+/// image_buffer.put_pixel(0, 0, Luma([120]));
+/// image_buffer.put_pixel(1, 0, Luma([200]));
+/// image_buffer.put_pixel(2, 0, Luma([255]));
+///
+/// # assert_eq!(single_l8_range_to_paths(&mut image_buffer.clone(), 100, 200, true), "M0 0H2V1H0Z");
+/// println!("{}", single_l8_range_to_paths(&mut image_buffer, 100, 200, true));
+/// ```
+pub fn single_l8_range_to_paths(buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, min: u8, max: u8, closepaths: bool) -> String {
+    trace_l8(buffer, |p| min <= p && p <= max, closepaths)
+}
+
+/*
+ Trace the pixels for which is_fg is true, with the sentinel values written into the image buffer
+*/
+fn trace_l8<F: Fn(u8) -> bool>(buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, is_fg: F, closepaths: bool) -> String {
     for p in buffer.pixels_mut() {
-        if p == &luma {
+        if is_fg(p[0]) {
             *p = Luma([L8_O_STARTING_VALUE]);
         }
         else {
@@ -73,6 +172,673 @@ pub fn single_l8_to_paths(buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, luma: Lum
     paths
 }
 
+/// Same as [`single_l8_to_paths`] but the image buffer is borrowed and not modified: the tracer works on a scratch copy of it,
+/// so the paths are exactly the same and the image can still be displayed afterwards.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::single_l8_to_paths_preserving;
+/// let mut image_buffer = GrayImage::new(3, 3);
+/// image_buffer.put_pixel(1, 1, Luma([1]));
+///
+/// # assert_eq!(single_l8_to_paths_preserving(&image_buffer, Luma([1]), true), "M1 1H2V2H1Z");
+/// println!("{}", single_l8_to_paths_preserving(&image_buffer, Luma([1]), true));
+/// assert_eq!(image_buffer.get_pixel(1, 1), &Luma([1]));
+/// ```
+pub fn single_l8_to_paths_preserving(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, closepaths: bool) -> String {
+    single_l8_to_paths(&mut buffer.clone(), luma, closepaths)
+}
+
+/// Same as [`single_l8_to_paths`] but the image buffer is restored after the tracing, without a copy of it: the foreground pixels
+/// are set back to `luma` and all the other pixels are set to `background`.
+///
+/// The restored buffer is the two-valued mask of the foreground, so it is byte-identical to the original buffer only if all
+/// its background pixels were equal to `background` (use [`single_l8_to_paths_preserving`] to keep any other value).
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::single_l8_to_paths_and_restore;
+/// let mut image_buffer = GrayImage::new(3, 3);
+/// image_buffer.put_pixel(1, 1, Luma([255]));
+/// image_buffer.put_pixel(2, 2, Luma([100]));
+///
+/// # assert_eq!(single_l8_to_paths_and_restore(&mut image_buffer, Luma([255]), Luma([0]), true), "M1 1H2V2H1Z");
+/// println!("{}", single_l8_to_paths_and_restore(&mut image_buffer, Luma([255]), Luma([0]), true));
+/// assert_eq!(image_buffer.get_pixel(1, 1), &Luma([255]));
+/// assert_eq!(image_buffer.get_pixel(2, 2), &Luma([0]));
+/// ```
+pub fn single_l8_to_paths_and_restore(buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, background: Luma<u8>, closepaths: bool) -> String {
+    let paths = single_l8_to_paths(buffer, luma, closepaths);
+    // The values of the traced foreground pixels only decrease from L8_O_STARTING_VALUE, and the ones of the background only increase from L8_H_STARTING_VALUE
+    for p in buffer.pixels_mut() {
+        *p = if p[0] < L8_DEFAULT_VALUE { luma } else { background };
+    }
+    paths
+}
+
+/// Same as [`single_l8_to_paths`] but the image can have any color type: it is converted to an 8-bit grayscale copy with
+/// [`DynamicImage::to_luma8`] first, so the image itself is not modified.
+///
+/// The conversion of the color images uses the luma of their RGB channels (`0.2126 R + 0.7152 G + 0.0722 B`, rounded), the alpha
+/// channel is dropped and the 16-bit or floating-point channels are scaled to `0..=255`: so `luma` is the converted value of the
+/// foreground color, e.g. `Luma([255])` for white or `Luma([0])` for black.
+/// # Examples
+/// ```edition2018
+/// # use image::{DynamicImage, Luma, Rgb, RgbImage};
+/// # use contour_tracing::image::dynamic_to_paths;
+/// let mut image_buffer = RgbImage::new(3, 3);
+/// image_buffer.put_pixel(1, 1, Rgb([255, 255, 255]));
+/// let image = DynamicImage::ImageRgb8(image_buffer);
+///
+/// # assert_eq!(dynamic_to_paths(&image, Luma([255]), true), "M1 1H2V2H1Z");
+/// println!("{}", dynamic_to_paths(&image, Luma([255]), true));
+/// ```
+pub fn dynamic_to_paths(image: &DynamicImage, luma: Luma<u8>, closepaths: bool) -> String {
+    single_l8_to_paths(&mut image.to_luma8(), luma, closepaths)
+}
+
+/// A function that takes an image buffer and an 8-bit luminance value as input and return the number of pixels with this value as output.
+///
+/// Unlike [`single_l8_to_paths`], the image buffer is not modified, e.g. to skip the empty frames before tracing them.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::count_foreground_l8;
+/// let mut image_buffer = GrayImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Luma([1]));
+/// image_buffer.put_pixel(2, 2, Luma([1]));
+///
+/// assert_eq!(count_foreground_l8(&image_buffer, Luma([1])), 2);
+/// ```
+pub fn count_foreground_l8(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>) -> u64 {
+    buffer.pixels().filter(|&p| *p == luma).count() as u64
+}
+
+/// A function that takes an RGBA image buffer, some channel thresholds and an option as input and return a string of SVG Path commands as output.
+///
+/// A pixel is in the foreground when it matches the [`ChannelThresholds`]. The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{Rgba, RgbaImage};
+/// # use contour_tracing::image::{rgba8_thresholds_to_paths, ChannelThresholds};
+/// let mut image_buffer = RgbaImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+/// image_buffer.put_pixel(1, 1, Rgba([200, 40, 0, 255]));
+/// image_buffer.put_pixel(2, 2, Rgba([200, 90, 0, 255]));
+///
+/// let thresholds = ChannelThresholds::new().red(129, 255).green(0, 63);
+/// # assert_eq!(rgba8_thresholds_to_paths(&image_buffer, &thresholds, true), "M0 0H1V1H0ZM1 1H2V2H1Z");
+/// println!("{}", rgba8_thresholds_to_paths(&image_buffer, &thresholds, true));
+/// ```
+pub fn rgba8_thresholds_to_paths(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>, thresholds: &ChannelThresholds, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| thresholds.matches(buffer.get_pixel(c as u32, r as u32)));
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes an RGBA image buffer, a color and an option as input and return a string of SVG Path commands as output.
+///
+/// The pixels equal to the color (the four channels, alpha included) are the foreground, e.g. the fully opaque red pixels of a sprite.
+/// The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{Rgba, RgbaImage};
+/// # use contour_tracing::image::single_rgba8_to_paths;
+/// let mut image_buffer = RgbaImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+/// image_buffer.put_pixel(1, 1, Rgba([255, 0, 0, 128]));
+/// image_buffer.put_pixel(2, 2, Rgba([255, 0, 0, 255]));
+///
+/// # assert_eq!(single_rgba8_to_paths(&image_buffer, Rgba([255, 0, 0, 255]), true), "M0 0H1V1H0ZM2 2H3V3H2Z");
+/// println!("{}", single_rgba8_to_paths(&image_buffer, Rgba([255, 0, 0, 255]), true));
+/// ```
+pub fn single_rgba8_to_paths(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>, color: Rgba<u8>, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| *buffer.get_pixel(c as u32, r as u32) == color);
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes an RGBA image buffer and an option as input and return a string of SVG Path commands as output.
+///
+/// The pixels which are not fully transparent (alpha not **0**) are the foreground, whatever their color, e.g. the silhouette of a sprite.
+/// The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{Rgba, RgbaImage};
+/// # use contour_tracing::image::single_rgba8_alpha_to_paths;
+/// let mut image_buffer = RgbaImage::new(3, 1);
+///
+/// image_buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+/// image_buffer.put_pixel(1, 0, Rgba([0, 0, 255, 1]));
+///
+/// # assert_eq!(single_rgba8_alpha_to_paths(&image_buffer, true), "M0 0H2V1H0Z");
+/// println!("{}", single_rgba8_alpha_to_paths(&image_buffer, true));
+/// ```
+pub fn single_rgba8_alpha_to_paths(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| buffer.get_pixel(c as u32, r as u32)[3] != 0);
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes an image buffer, an 8-bit luminance value, a frame boundary and an option as input and return a string of SVG Path commands as output.
+///
+/// With [`FrameBoundary::Closed`], the paths are the ones of [`single_l8_to_paths`] (and of `array::bits_to_paths`
+/// for the same foreground): a region which touches the four sides of the image is outlined by the image rectangle.
+/// With [`FrameBoundary::Open`], the edges along the sides of the image are not traced, so the contours which touch the sides
+/// are split into open paths. The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::{contour::FrameBoundary, image::single_l8_to_paths_with_frame};
+/// let mut image_buffer = GrayImage::from_pixel(3, 3, Luma([1]));
+/// image_buffer.put_pixel(1, 1, Luma([0]));
+///
+/// # assert_eq!(single_l8_to_paths_with_frame(&image_buffer, Luma([1]), FrameBoundary::Closed, true), "M0 0H3V3H0ZM1 1V2H2V1Z");
+/// # assert_eq!(single_l8_to_paths_with_frame(&image_buffer, Luma([1]), FrameBoundary::Open, true), "M1 1V2H2V1Z");
+/// println!("{}", single_l8_to_paths_with_frame(&image_buffer, Luma([1]), FrameBoundary::Open, true));
+/// ```
+pub fn single_l8_to_paths_with_frame(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, frame: FrameBoundary, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| *buffer.get_pixel(c as u32, r as u32) == luma);
+    let corners = ((0, 0), (buffer.width() as i32, buffer.height() as i32));
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| match frame {
+        FrameBoundary::Open => match super::contour::frame_runs(points, corners.0, corners.1) {
+            Some(runs) => runs.iter().for_each(|run| super::contour::push_path(&mut paths, run, false)),
+            None => super::contour::push_path(&mut paths, points, closepaths),
+        },
+        FrameBoundary::Closed => super::contour::push_path(&mut paths, points, closepaths),
+    });
+    paths
+}
+
+/// A function that takes an RGB image buffer, a color, a tolerance and an option as input and return a string of SVG Path commands as output.
+///
+/// A pixel is in the foreground when the squared Euclidean distance between its color and `color` in RGB space
+/// (the sum of the squared differences of the three 8-bit channels, from `0` to `3 * 255 * 255`) is lower than or equal to the `tolerance`,
+/// e.g. a `tolerance` of `100` accepts a difference of `10` on a single channel. A `tolerance` of `0` is an exact match.
+/// The image buffer is not modified (unlike [`single_l8_to_paths`]), e.g. for the JPEG artifacts around a sprite.
+/// # Examples
+/// ```edition2018
+/// # use image::{Rgb, RgbImage};
+/// # use contour_tracing::image::color_to_paths_tolerant;
+/// let mut image_buffer = RgbImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Rgb([255, 0, 0]));
+/// image_buffer.put_pixel(1, 1, Rgb([250, 5, 0]));
+/// image_buffer.put_pixel(2, 2, Rgb([240, 0, 0]));
+///
+/// # assert_eq!(color_to_paths_tolerant(&image_buffer, Rgb([255, 0, 0]), 100, true), "M0 0H1V1H0ZM1 1H2V2H1Z");
+/// println!("{}", color_to_paths_tolerant(&image_buffer, Rgb([255, 0, 0]), 100, true));
+/// ```
+pub fn color_to_paths_tolerant(buffer: &ImageBuffer<Rgb<u8>, Vec<u8>>, color: Rgb<u8>, tolerance: u32, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| {
+        let pixel = buffer.get_pixel(c as u32, r as u32);
+        let distance: u32 = (0..3).map(|i| (pixel[i] as i32 - color[i] as i32).pow(2) as u32).sum();
+        distance <= tolerance
+    });
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes an image buffer, an 8-bit luminance value, an option and a region of interest as input and return
+/// a string of SVG Path commands as output.
+///
+/// Only the pixels inside `roi` are scanned (the maximum coordinates are exclusive, and the region is clipped to the image),
+/// as if the pixels outside were background: the shapes cut by the region are closed along its sides. The coordinates are
+/// the ones of the whole image, so the tiles of an image can be traced independently. The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::contour::BBox;
+/// # use contour_tracing::image::single_l8_roi_to_paths;
+/// let mut image_buffer = GrayImage::new(4, 4);
+/// let foreground_color: image::Luma<u8> = Luma([1]);
+///
+/// for x in 0..4 {
+///     image_buffer.put_pixel(x, 2, foreground_color);
+/// }
+///
+/// let roi = BBox { min_x: 1, min_y: 1, max_x: 3, max_y: 4 };
+/// # assert_eq!(single_l8_roi_to_paths(&image_buffer, foreground_color, true, roi), "M1 2H3V3H1Z");
+/// println!("{}", single_l8_roi_to_paths(&image_buffer, foreground_color, true, roi));
+/// ```
+pub fn single_l8_roi_to_paths(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, closepaths: bool, roi: BBox) -> String {
+    let mut paths = String::new();
+    let (x0, y0) = (roi.min_x.max(0) as u32, roi.min_y.max(0) as u32);
+    let (x1, y1) = ((roi.max_x.max(0) as u32).min(buffer.width()), (roi.max_y.max(0) as u32).min(buffer.height()));
+    if x0 >= x1 || y0 >= y1 {
+        return paths;
+    }
+    let mut contours = super::grid::bordered_par((y1 - y0) as usize, (x1 - x0) as usize, |r, c| *buffer.get_pixel(x0 + c as u32, y0 + r as u32) == luma);
+    let mut shifted: Vec<(i32, i32)> = Vec::new();
+    super::grid::scan(&mut contours, |_, points| {
+        shifted.clear();
+        shifted.extend(points.iter().map(|p| (p.0 + x0 as i32, p.1 + y0 as i32)));
+        super::contour::push_path(&mut paths, &shifted, closepaths);
+    });
+    paths
+}
+
+/// A function that takes a 16-bit grayscale image buffer, a 16-bit luminance value and an option as input and return a string of SVG Path commands as output.
+///
+/// The pixels equal to `luma` are the foreground, so the distinct 16-bit labels are never aliased together. The image buffer is not
+/// modified (the contours are traced in a separate grid instead of the sentinel values of [`single_l8_to_paths`]), and the paths are
+/// the ones of [`single_l8_to_paths`] for the same foreground.
+/// # Examples
+/// ```edition2018
+/// # use image::{ImageBuffer, Luma};
+/// # use contour_tracing::image::single_l16_to_paths;
+/// let mut image_buffer: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Luma([1000]));
+/// image_buffer.put_pixel(1, 1, Luma([1001]));
+/// image_buffer.put_pixel(2, 2, Luma([1000]));
+///
+/// # assert_eq!(single_l16_to_paths(&image_buffer, Luma([1000]), true), "M0 0H1V1H0ZM2 2H3V3H2Z");
+/// println!("{}", single_l16_to_paths(&image_buffer, Luma([1000]), true));
+/// ```
+pub fn single_l16_to_paths(buffer: &ImageBuffer<Luma<u16>, Vec<u16>>, luma: Luma<u16>, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| *buffer.get_pixel(c as u32, r as u32) == luma);
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes a floating-point grayscale image buffer, a threshold and an option as input and return a string of SVG Path commands as output.
+///
+/// A pixel is in the foreground when its value is greater than or equal to the `threshold`, the **NaN** pixels are always in the background.
+/// The image buffer is not modified (the contours are traced in a separate grid), e.g. for HDR images or heightmaps.
+/// # Examples
+/// ```edition2018
+/// # use image::{ImageBuffer, Luma};
+/// # use contour_tracing::image::single_f32_to_paths;
+/// let mut image_buffer: ImageBuffer<Luma<f32>, Vec<f32>> = ImageBuffer::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Luma([1.5]));
+/// image_buffer.put_pixel(1, 1, Luma([f32::NAN]));
+/// image_buffer.put_pixel(2, 2, Luma([0.5]));
+///
+/// # assert_eq!(single_f32_to_paths(&image_buffer, 0.5, true), "M0 0H1V1H0ZM2 2H3V3H2Z");
+/// println!("{}", single_f32_to_paths(&image_buffer, 0.5, true));
+/// ```
+pub fn single_f32_to_paths(buffer: &ImageBuffer<Luma<f32>, Vec<f32>>, threshold: f32, closepaths: bool) -> String {
+    let mut contours = super::grid::bordered_par(buffer.height() as usize, buffer.width() as usize, |r, c| buffer.get_pixel(c as u32, r as u32)[0] >= threshold);
+    let mut paths = String::new();
+    super::grid::scan(&mut contours, |_, points| super::contour::push_path(&mut paths, points, closepaths));
+    paths
+}
+
+/// A function that takes an anti-aliased grayscale image buffer (the coverage of each pixel), an option and a precision as input
+/// and return a string of SVG Path commands with sub-pixel coordinates as output.
+///
+/// The topology is the one of a hard threshold at 50% coverage: a pixel is in the foreground when its value is greater than or
+/// equal to **128**, and the pixels outside the image have no coverage. Then, instead of the corners of the pixels, there is
+/// one vertex for each side between a foreground pixel and a background pixel, on the segment between their centers, where
+/// the coverage linearly interpolated along the segment is 50% (like marching squares). A hard mask (only **0** and **255**)
+/// thus gives the midpoints of the sides of the pixels, i.e. the corners are cut at 45 degrees. The collinear vertices are merged
+/// and the vertices are joined with SVG Path **L** commands. The coordinates are rounded to `precision` decimals and given
+/// without their trailing zeros, e.g. `3` instead of `3.00`. The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::coverage_l8_to_paths;
+/// let mut image_buffer = GrayImage::new(3, 1);
+///
+/// image_buffer.put_pixel(0, 0, Luma([255]));
+/// image_buffer.put_pixel(1, 0, Luma([170]));
+///
+/// # assert_eq!(coverage_l8_to_paths(&image_buffer, true, 3), "M0.5 0L1.5 0.25L1.75 0.5L1.5 0.75L0.5 1L0 0.5Z");
+/// println!("{}", coverage_l8_to_paths(&image_buffer, true, 3));
+/// ```
+pub fn coverage_l8_to_paths(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, closepaths: bool, precision: u8) -> String {
+    let (width, height) = (buffer.width() as i32, buffer.height() as i32);
+    let coverage = |x: i32, y: i32| if x >= 0 && y >= 0 && x < width && y < height { buffer.get_pixel(x as u32, y as u32)[0] as f64 / 255.0 } else { 0.0 };
+    let mut contours = super::grid::bordered_par(height as usize, width as usize, |r, c| buffer.get_pixel(c as u32, r as u32)[0] >= 128);
+    let mut paths = String::new();
+    let mut vertices: Vec<(f64, f64)> = Vec::new();
+    super::grid::scan(&mut contours, |_, points| {
+        vertices.clear();
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let step = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+            let mut p = a;
+            while p != b {
+                // The foreground pixel is on the right-hand side of the side, the background pixel on the left-hand side
+                let (fg, bg) = match step {
+                    (1, _) => ((p.0, p.1), (p.0, p.1 - 1)),
+                    (-1, _) => ((p.0 - 1, p.1 - 1), (p.0 - 1, p.1)),
+                    (_, 1) => ((p.0 - 1, p.1), (p.0, p.1)),
+                    _ => ((p.0, p.1 - 1), (p.0 - 1, p.1 - 1)),
+                };
+                let (inside, outside) = (coverage(fg.0, fg.1), coverage(bg.0, bg.1));
+                let t = (inside - 0.5) / (inside - outside);
+                let vertex = (fg.0 as f64 + 0.5 + t * (bg.0 - fg.0) as f64, fg.1 as f64 + 0.5 + t * (bg.1 - fg.1) as f64);
+                while vertices.len() >= 2 && {
+                    let (u, v) = (vertices[vertices.len() - 2], vertices[vertices.len() - 1]);
+                    ((v.0 - u.0) * (vertex.1 - v.1) - (v.1 - u.1) * (vertex.0 - v.0)).abs() < 1e-9
+                } {
+                    vertices.pop();
+                }
+                vertices.push(vertex);
+                p = (p.0 + step.0, p.1 + step.1);
+            }
+        }
+        // The same around the first vertex
+        while vertices.len() >= 3 && {
+            let (u, v, w) = (vertices[vertices.len() - 2], vertices[vertices.len() - 1], vertices[0]);
+            ((v.0 - u.0) * (w.1 - v.1) - (v.1 - u.1) * (w.0 - v.0)).abs() < 1e-9
+        } {
+            vertices.pop();
+        }
+        for (i, v) in vertices.iter().enumerate() {
+            paths.push_str(&format!("{}{} {}", if i == 0 { "M" } else { "L" }, super::contour::number(v.0, precision), super::contour::number(v.1, precision)));
+        }
+        if closepaths {
+            paths.push('Z');
+        }
+    });
+    paths
+}
+
+/// A function that takes a decoder of an 8-bit grayscale image, an 8-bit luminance value and an option as input and
+/// return a string of SVG Path commands as output, while the image is decoded row by row.
+///
+/// The whole image is never held in memory: only the previous row and the open contours are kept, e.g. for very large PNG files
+/// with [`PngDecoder`](::image::codecs::png::PngDecoder) which decodes the rows on demand. The contours are followed
+/// along the sides of the pixels and sorted into the order of [`single_l8_to_paths`] when they are closed.
+///
+/// The paths are the same as those of [`single_l8_to_paths`].
+///
+/// An error is returned if the decoder does not decode an 8-bit grayscale image, or if the decoding fails.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, ImageOutputFormat, Luma};
+/// # use image::codecs::png::PngDecoder;
+/// # use contour_tracing::image::stream_l8_to_paths;
+/// let mut image_buffer = GrayImage::new(3, 3);
+/// image_buffer.put_pixel(1, 1, Luma([1]));
+/// let mut png = std::io::Cursor::new(Vec::new());
+/// image_buffer.write_to(&mut png, ImageOutputFormat::Png).unwrap();
+/// let png = png.into_inner();
+///
+/// let decoder = PngDecoder::new(png.as_slice()).unwrap();
+/// # assert_eq!(stream_l8_to_paths(decoder, Luma([1]), true).unwrap(), "M1 1H2V2H1Z");
+/// # let decoder = PngDecoder::new(png.as_slice()).unwrap();
+/// println!("{}", stream_l8_to_paths(decoder, Luma([1]), true).unwrap());
+/// ```
+pub fn stream_l8_to_paths<'a, D: ImageDecoder<'a>>(decoder: D, luma: Luma<u8>, closepaths: bool) -> ImageResult<String> {
+    if decoder.color_type() != ColorType::L8 {
+        let kind = UnsupportedErrorKind::Color(decoder.original_color_type());
+        return Err(ImageError::Unsupported(UnsupportedError::from_format_and_kind(ImageFormatHint::Unknown, kind)));
+    }
+    let (width, height) = decoder.dimensions();
+    #[allow(deprecated)] // The only way to read the rows on demand with image 0.24
+    let mut reader = decoder.into_reader()?;
+    let mut bytes = vec![0u8; width as usize];
+    let mut row = vec![false; width as usize];
+    let mut tracer = super::grid::RowTracer::new(width as usize);
+    for _ in 0..height {
+        reader.read_exact(&mut bytes).map_err(ImageError::IoError)?;
+        for (cell, &byte) in row.iter_mut().zip(&bytes) {
+            *cell = byte == luma[0];
+        }
+        tracer.push_row(&row);
+    }
+    let mut paths = String::new();
+    for (_, points) in tracer.finish() {
+        super::contour::push_path(&mut paths, &points, closepaths);
+    }
+    Ok(paths)
+}
+
+/// A function that takes an image buffer, a luminance value and a color as input and return a copy of the image buffer
+/// with the contours drawn in the color as output.
+///
+/// The contours of the pixels with the luminance value are traced, then the foreground pixels along the contours
+/// (the pixels on the inner side of the edges of the outlines and of the holes) are drawn in the color, e.g. for a quick visual check.
+/// The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma, Rgba};
+/// # use contour_tracing::image::overlay_contours;
+/// let image_buffer = GrayImage::from_pixel(3, 3, Luma([255]));
+///
+/// let overlay = overlay_contours(&image_buffer, Luma([255]), Rgba([255, 0, 0, 255]));
+/// # assert_eq!(overlay.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+/// # assert_eq!(overlay.get_pixel(1, 1), &Rgba([255, 255, 255, 255]));
+/// println!("{:?}", overlay.get_pixel(0, 0));
+/// ```
+pub fn overlay_contours(source: &ImageBuffer<Luma<u8>, Vec<u8>>, luma: Luma<u8>, color: Rgba<u8>) -> RgbaImage {
+    let mut overlay = RgbaImage::from_fn(source.width(), source.height(), |x, y| {
+        let l = source.get_pixel(x, y)[0];
+        Rgba([l, l, l, 255])
+    });
+    let mut contours = super::grid::bordered_par(source.height() as usize, source.width() as usize, |r, c| *source.get_pixel(c as u32, r as u32) == luma);
+    super::grid::scan(&mut contours, |_, points| {
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let step = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+            let mut p = a;
+            while p != b {
+                // The foreground pixel is on the right-hand side of the edge
+                let (x, y) = match step { (1, _) => (p.0, p.1), (-1, _) => (p.0 - 1, p.1 - 1), (_, 1) => (p.0 - 1, p.1), _ => (p.0, p.1 - 1) };
+                overlay.put_pixel(x as u32, y as u32, color);
+                p = (p.0 + step.0, p.1 + step.1);
+            }
+        }
+    });
+    overlay
+}
+
+/// A function that takes a label image buffer and a background luminance value as input and return,
+/// for each label, its outline/hole hierarchy as output.
+///
+/// Each [`OutlineNode`] is a 4-connected component of a label, with its id (in scan order, starting at 0 for each label),
+/// its outline, its holes (and the outlines inside the holes); the areas and bounding boxes are given by [`OutlineNode::area`]
+/// and [`Contour::bbox`](super::contour::Contour::bbox). The image buffer is read once and all the labels are traced
+/// in a single scan, their hierarchies being built from the nesting of the contours found by the scan. The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::labels_l8_to_hierarchy;
+/// let mut image_buffer = GrayImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Luma([1]));
+/// image_buffer.put_pixel(1, 1, Luma([2]));
+/// image_buffer.put_pixel(2, 2, Luma([1]));
+///
+/// let hierarchy = labels_l8_to_hierarchy(&image_buffer, Luma([0]));
+/// # assert_eq!(hierarchy[&1].len(), 2);
+/// # assert_eq!(hierarchy[&2][0].outline.points, vec![(1, 1), (2, 1), (2, 2), (1, 2)]);
+/// for (label, components) in hierarchy {
+///     println!("{}: {} component(s)", label, components.len());
+/// }
+/// ```
+pub fn labels_l8_to_hierarchy(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, background: Luma<u8>) -> BTreeMap<u8, Vec<OutlineNode>> {
+    let (width, height) = (buffer.width() as usize, buffer.height() as usize);
+    let mut grid: Vec<Vec<Option<u8>>> = vec![vec![None; width + 2]; height + 2];
+    for (x, y, p) in buffer.enumerate_pixels() {
+        if *p != background {
+            grid[y as usize + 1][x as usize + 1] = Some(p[0]);
+        }
+    }
+    let mut traced: BTreeMap<u8, (Vec<Contour>, Vec<Option<usize>>)> = BTreeMap::new();
+    let mut ids: Vec<usize> = Vec::new(); // The index of each contour among the contours of its label
+    for (label, contour, parent) in super::grid::scan_labels(&grid) {
+        let (contours, parents) = traced.entry(label).or_default();
+        ids.push(contours.len());
+        contours.push(contour);
+        parents.push(parent.map(|p| ids[p]));
+    }
+    traced.into_iter().map(|(label, (contours, parents))| (label, super::contour::hierarchy_with(contours, &parents))).collect()
+}
+
+/// A function that takes an image buffer, some 8-bit luminance values and an option as input and return a map
+/// from each luminance value to its string of SVG Path commands as output.
+///
+/// The paths of a label are the ones of [`single_l8_to_paths`] for this label, and the pixels with other values are ignored.
+/// The image buffer is read once to find the bounding box of each label, then each label is traced within its own bounding box
+/// instead of a full scan of the image for each label. Each label is in the map, with an empty string if no pixel has its value.
+/// The image buffer is not modified.
+/// # Examples
+/// ```edition2018
+/// # use image::{GrayImage, Luma};
+/// # use contour_tracing::image::multi_l8_to_paths;
+/// let mut image_buffer = GrayImage::new(3, 3);
+///
+/// image_buffer.put_pixel(0, 0, Luma([1]));
+/// image_buffer.put_pixel(1, 1, Luma([2]));
+/// image_buffer.put_pixel(2, 2, Luma([1]));
+///
+/// let paths = multi_l8_to_paths(&image_buffer, &[Luma([1]), Luma([2])], true);
+/// # assert_eq!(paths[&1], "M0 0H1V1H0ZM2 2H3V3H2Z");
+/// # assert_eq!(paths[&2], "M1 1H2V2H1Z");
+/// println!("{}", paths[&1]);
+/// ```
+pub fn multi_l8_to_paths(buffer: &ImageBuffer<Luma<u8>, Vec<u8>>, labels: &[Luma<u8>], closepaths: bool) -> HashMap<u8, String> {
+    let mut requested = [false; 256];
+    for label in labels {
+        requested[label[0] as usize] = true;
+    }
+    let mut bboxes: [Option<(u32, u32, u32, u32)>; 256] = [None; 256];
+    for (x, y, p) in buffer.enumerate_pixels() {
+        if requested[p[0] as usize] {
+            let b = bboxes[p[0] as usize].get_or_insert((x, y, x, y));
+            *b = (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y));
+        }
+    }
+    let mut paths = HashMap::new();
+    for label in labels {
+        let mut label_paths = String::new();
+        if let Some((x0, y0, x1, y1)) = bboxes[label[0] as usize] {
+            let mut contours = super::grid::bordered_par((y1 - y0 + 1) as usize, (x1 - x0 + 1) as usize, |r, c| buffer.get_pixel(x0 + c as u32, y0 + r as u32) == label);
+            super::grid::scan(&mut contours, |_, points| {
+                let shifted: Vec<(i32, i32)> = points.iter().map(|p| (p.0 + x0 as i32, p.1 + y0 as i32)).collect();
+                super::contour::push_path(&mut label_paths, &shifted, closepaths);
+            });
+        }
+        paths.insert(label[0], label_paths);
+    }
+    paths
+}
+
+/// A function that takes some contours, a width, a height and an option as input and return an image buffer
+/// (foreground pixels are **255**, background pixels are **0**) as output.
+///
+/// It is the inverse of the tracing: the contours are filled with the nonzero winding rule, so the holes (wound
+/// in the opposite direction of the outlines) are not filled, and the parts of the contours outside the image are ignored.
+/// With the **antialias option** set to **true**, each pixel is set to the exact area of the pixel covered by the contours
+/// (e.g. for a mask after a [`Contour::simplify`]), otherwise a pixel is in the foreground when at least half of it is covered.
+/// # Examples
+/// ```edition2018
+/// # use contour_tracing::{contour::Contour, image::rasterize_contours};
+/// let triangle = Contour::new(vec![(0, 0), (2, 2), (0, 2)], false);
+///
+/// let mask = rasterize_contours(&[triangle.clone()], 2, 2, false);
+/// # assert_eq!(mask.into_raw(), vec![255, 0, 255, 255]);
+/// let antialiased = rasterize_contours(&[triangle], 2, 2, true);
+/// # assert_eq!(antialiased.into_raw(), vec![128, 0, 255, 128]);
+/// ```
+pub fn rasterize_contours(contours: &[Contour], width: u32, height: u32, antialias: bool) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+    let (w, h) = (width as usize, height as usize);
+    let mut acc = vec![0f64; (w + 1) * h];
+    for contour in contours {
+        let n = contour.points.len();
+        for i in 0..n {
+            let (a, b) = (contour.points[i], contour.points[(i + 1) % n]);
+            accumulate_line(&mut acc, w, h, (a.0 as f64, a.1 as f64), (b.0 as f64, b.1 as f64));
+        }
+    }
+    let mut buffer: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::new(width, height);
+    for (y, line) in acc.chunks(w + 1).enumerate() {
+        let mut coverage = 0.0;
+        for (x, a) in line[..w].iter().enumerate() {
+            coverage += a;
+            let c = coverage.abs().min(1.0);
+            let luma = if antialias { (c * 255.0).round() as u8 } else if c >= 0.5 { 255 } else { 0 };
+            buffer.put_pixel(x as u32, y as u32, Luma([luma]));
+        }
+    }
+    buffer
+}
+
+/*
+ Accumulate the signed area covered on the right of a line into the rows of acc (with a width of w + 1),
+ the prefix sums of a row are the winding numbers weighted by the coverage of the pixels
+*/
+fn accumulate_line(acc: &mut [f64], w: usize, h: usize, p0: (f64, f64), p1: (f64, f64)) {
+    if p0.1 == p1.1 {
+        return;
+    }
+    let (dir, p0, p1) = if p0.1 < p1.1 { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+    let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+    let (top, bottom) = (p0.1.max(0.0), p1.1.min(h as f64));
+    if top >= bottom {
+        return;
+    }
+    let mut x = p0.0 + (top - p0.1) * dxdy;
+    for y in top as usize..bottom.ceil() as usize {
+        let dy = (y as f64 + 1.0).min(bottom) - (y as f64).max(top);
+        let xnext = x + dxdy * dy;
+        let d = dy * dir;
+        let line = &mut acc[y * (w + 1)..(y + 1) * (w + 1)];
+        // Split the line at the left and right sides of the image: the left parts cover the whole row, the right parts nothing
+        let (x0, x1) = if x < xnext { (x, xnext) } else { (xnext, x) };
+        let mut cuts = vec![x0];
+        cuts.extend([0.0, w as f64].iter().filter(|&&c| c > x0 && c < x1));
+        cuts.push(x1);
+        for pair in cuts.windows(2) {
+            let share = if x1 > x0 { d * (pair[1] - pair[0]) / (x1 - x0) } else { d };
+            let (a, b) = (pair[0].max(0.0).min(w as f64), pair[1].max(0.0).min(w as f64));
+            accumulate_span(line, a, b, share);
+        }
+        x = xnext;
+    }
+}
+
+/*
+ Accumulate a span [x0, x1] of a line crossing a row with a signed height d
+*/
+fn accumulate_span(line: &mut [f64], x0: f64, x1: f64, d: f64) {
+    let w = line.len() - 1;
+    let x0i = (x0.floor() as usize).min(w);
+    let x1i = x1.ceil() as usize;
+    if x1i <= x0i + 1 {
+        let xmf = 0.5 * (x0 + x1) - x0i as f64;
+        line[x0i] += d - d * xmf;
+        if x0i < w {
+            line[x0i + 1] += d * xmf;
+        }
+    } else {
+        let s = 1.0 / (x1 - x0);
+        let x0f = x0 - x0i as f64;
+        let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+        let x1f = x1 - x1i as f64 + 1.0;
+        let am = 0.5 * s * x1f * x1f;
+        line[x0i] += d * a0;
+        if x1i == x0i + 2 {
+            line[x0i + 1] += d * (1.0 - a0 - am);
+        } else {
+            let a1 = s * (1.5 - x0f);
+            line[x0i + 1] += d * (a1 - a0);
+            for cell in &mut line[x0i + 2..x1i - 1] {
+                *cell += d * s;
+            }
+            let a2 = a1 + (x1i - x0i - 3) as f64 * s;
+            line[x1i - 1] += d * (1.0 - a2 - am);
+        }
+        line[x1i] += d * am;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn trace_single_l8(outline: bool, cursor_x: u32, cursor_y: u32, mut o: [usize; 8], rot: i8, viv: (usize, usize, usize), vertex: [(i8, i8); 7], value: [i8; 7], buffer: &mut ImageBuffer<Luma<u8>, Vec<u8>>, paths: &mut String, closepaths: bool) {
     let mut tracer_x = cursor_x;
     let mut tracer_y = cursor_y;
@@ -145,7 +911,6 @@ fn trace_single_l8(outline: bool, cursor_x: u32, cursor_y: u32, mut o: [usize; 8
             break;
         }
         o.rotate_left(rot.rem_euclid(8) as usize);
-        vertices_nbr += 1;
         if o[0] == 0 || o[0] == 4 { paths.push_str(&format!("H{}", tracer_x.wrapping_add(vertex[o[0]].0 as u32))); } else { paths.push_str(&format!("V{}", tracer_y.wrapping_add(vertex[o[0]].1 as u32))); }
     }
     if closepaths { paths.push('Z'); }
@@ -11,20 +11,29 @@
 //!
 //! # Features
 //! Core features:
-//! - Trace contours using the Theo Pavlidis' algorithm (connectivity: 4-connected)
+//! - Trace contours using the Theo Pavlidis' algorithm (connectivity: 4-connected, or 8-connected with the array feature)
 //! - Trace **outlines** in **clockwise direction**
 //! - Trace **holes** in **counterclockwise direction**
 //! - Input format: a 2D array of bits or an image buffer
-//! - Output format: a string of SVG Path commands
+//! - Output format: a string of SVG Path commands or a vec of contours
 //!
 //! Manual parameters:
 //! - User can specify to close or not the paths (with the SVG Path **Z** command)
+//!
+//! Optional features:
+//! - **geo**: the contours and the polygons of an array of bits can be converted to the `geo_types` polygons of the `geo` crate (this feature needs the Rust version of `geo-types`)
+//! - **geojson**: the polygons of an array of bits can be serialized to a GeoJSON FeatureCollection
+//! - **rayon**: the foreground of large inputs is detected in parallel, and the connected components of an array of bits can be traced in parallel
+//! - **serde**: the contours, their metadata and their hierarchy can be serialized and deserialized with serde
+//! - **stream**: the SVG Path commands of the contours of an array of bits can be yielded incrementally as a `futures::Stream`
+//! - **tessellation**: the outlines and their holes can be split into triangles
 //! 
 //! # Examples
 //! Have a look at the different functions inside the modules below.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(any(feature = "array", feature = "image"))]
 const MN: [(i8, i8); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)]; // Moore neighborhood
 
 /*
@@ -49,6 +58,13 @@ const MN: [(i8, i8); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-
 #[cfg_attr(docsrs, doc(cfg(feature = "array")))]
 pub mod array;
 
+#[cfg(any(feature = "array", feature = "image"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "array", feature = "image"))))]
+pub mod contour;
+
+#[cfg(any(feature = "array", feature = "image"))]
+mod grid;
+
 #[cfg(feature = "image")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
 pub mod image;
@@ -3,7 +3,8 @@
 #[cfg(test)]
 #[cfg(feature = "array")]
 mod array {
-    use contour_tracing::array::bits_to_paths;
+    use contour_tracing::array::{bits_nonzero_to_paths, bits_to_contours, bits_to_contours_and_simplified, bits_to_contours_meta, bits_to_contours_with_ids, bits_to_contours_with_options, bits_to_fill_and_offset_paths, bits_to_glyph_paths, bits_to_hierarchy, bits_to_labeled_paths, bits_to_opencv_contours, bits_to_path_elements, bits_to_path_fragments, bits_to_path_list, bits_to_paths, bits_to_paths_filtered, bits_to_paths_for_fill_rule, bits_to_paths_into, bits_to_paths_offset, bits_to_paths_ref, bits_to_paths_scaled, bits_to_paths_simplified, bits_to_paths_smoothed, bits_to_paths_transformed, bits_to_paths_with, bits_to_paths_with_bbox, bits_to_paths_with_borders, bits_to_paths_with_lengths, bits_to_paths_with_options, bits_to_paths_within_bounds, bits_to_polygon_rings, bits_to_rectangles, bits_to_svg_document, bools_to_paths, bytes_to_paths, contours_equal, diff_paths, estimate_path_len, first_contour_path, flat_bits_to_paths, foreground_bbox, mask_to_paths, masked_trace, some_labels_to_paths, some_to_paths, trace_fn, trace_into, trace_into_arena, trace_visit, try_bits_to_paths, write_paths, BitTracer, BoundsPolicy, Connectivity, ContourFilter, ContourVisitor, ContourWalk, CornerStyle, DimensionMismatch, Direction, FillRule, FontWinding, OutOfBounds, OutputOrder, ScanOrder, SvgDocOptions, TileBorders, TraceError, TraceOptions, PATH_ELEMENT_TEMPLATE};
+    use contour_tracing::contour::{BBox, Contour, ContourMeta, FrameBoundary, HoleNode, OutlineNode};
 
     #[test]
     fn bits_to_paths_001() {
@@ -132,4 +133,1336 @@ mod array {
 
         assert_eq!(bits_to_paths(bits, false), "M0 0H51V26H0M1 1V25H50V1M2 2H49V24H2M3 3V23H48V3M41 4H46V5H47V10H46V11H41V10H40V5H41M4 5H9V10H4M19 5H22V6H21V7H22V6H23V9H22V8H21V9H22V10H19V9H20V8H19V9H18V6H19V7H20V6H19M28 5H33V10H28M35 5H38V6H37V7H36V6H35M42 5V6H43V7H44V6H45V5M5 6V9H8V6M10 6H13V9H10M15 6H16V7H17V8H16V9H15V8H14V7H15M24 6H25V7H24M26 6H27V7H26M29 6V7H30V6M31 6V7H32V6M34 6H35V7H36V8H35V9H34M38 6H39V9H38V8H37V7H38M41 6V9H42V8H43V7H42V6M45 6V7H44V8H45V9H46V6M6 7H7V8H6M11 7V8H12V7M25 7H26V8H25M30 7V8H31V7M24 8H25V9H24M26 8H27V9H26M29 8V9H30V8M31 8V9H32V8M36 8H37V9H38V10H35V9H36M43 8V9H42V10H45V9H44V8M22 12H41V22H22M4 13H11V19H6V15H9V17H8V16H7V18H10V14H5V20H12V13H21V21H14V15H19V19H16V17H17V18H18V16H15V20H20V14H13V21H4M23 13V21H32V14H39V20H34V16H37V18H36V17H35V19H38V15H33V21H40V13H31V20H24V14H29V18H26V16H27V17H28V15H25V19H30V13");
     }
+
+    #[test]
+    fn trace_visit_001() {
+        // The SVG Path commands and the contours as visitors
+        struct Paths(String, Option<(i32, i32)>);
+        impl ContourVisitor for Paths {
+            fn begin_contour(&mut self, _is_hole: bool) { self.1 = None; }
+            fn vertex(&mut self, x: i32, y: i32) {
+                match self.1 {
+                    None => self.0.push_str(&format!("M{} {}", x, y)),
+                    Some((_, py)) if py == y => self.0.push_str(&format!("H{}", x)),
+                    Some(_) => self.0.push_str(&format!("V{}", y)),
+                }
+                self.1 = Some((x, y));
+            }
+            fn end_contour(&mut self) { self.0.push('Z'); }
+        }
+        struct Contours(Vec<Contour>);
+        impl ContourVisitor for Contours {
+            fn begin_contour(&mut self, is_hole: bool) { self.0.push(Contour::new(vec![], is_hole)); }
+            fn vertex(&mut self, x: i32, y: i32) { self.0.last_mut().unwrap().points.push((x, y)); }
+            fn end_contour(&mut self) {}
+        }
+
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+        let mut paths = Paths(String::new(), None);
+        trace_visit(&bits, &mut paths);
+        assert_eq!(paths.0, bits_to_paths(bits.to_vec(), true));
+        let mut contours = Contours(Vec::new());
+        trace_visit(&bits, &mut contours);
+        assert_eq!(contours.0, bits_to_contours(&bits));
+    }
+
+    #[test]
+    fn bit_tracer_001() {
+        let frames = vec![vec![vec![ 1,1,1 ],
+                               vec![ 1,0,1 ],
+                               vec![ 1,1,1 ]],
+                          vec![vec![ 0,0,0 ],
+                               vec![ 0,1,0 ],
+                               vec![ 0,0,0 ]],
+                          vec![vec![ 1,0 ],
+                               vec![ 0,1 ]]];
+
+        let mut tracer = BitTracer::new(3, 3);
+        for bits in frames.iter().chain(&frames) {
+            assert_eq!(tracer.trace(bits, true), bits_to_paths_ref(bits, true));
+            assert_eq!(tracer.trace(bits, false), bits_to_paths_ref(bits, false));
+        }
+        assert_eq!(tracer.trace(&[vec![]], true), "");
+    }
+
+    #[test]
+    fn bits_nonzero_to_paths_001() {
+        let sevens = vec![vec![7i8; 4]; 3];
+        assert_eq!(bits_nonzero_to_paths(&sevens, true), "M0 0H4V3H0Z");
+        assert_eq!(bits_to_paths(sevens, true), "");
+
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+        assert_eq!(bits_nonzero_to_paths(&bits, false), bits_to_paths(bits, false));
+    }
+
+    #[test]
+    fn mask_to_paths_001() {
+        let bits = vec![vec![ 1,2,1,0 ],
+                        vec![ 1,0,-1,1 ],
+                        vec![ 1,1,1,0 ]];
+
+        // Only the 1 bits are the foreground of bits_to_paths
+        let bools: Vec<Vec<bool>> = bits.iter().map(|row| row.iter().map(|&bit| bit == 1).collect()).collect();
+        assert_eq!(bools_to_paths(&bools, true), bits_to_paths(bits.to_vec(), true));
+        assert_eq!(mask_to_paths(&bits, |&bit| bit == 1, false), bits_to_paths(bits.to_vec(), false));
+        assert_eq!(mask_to_paths(&bits, |&bit| bit != 0, true), "M0 0H3V1H4V2H3V3H0ZM1 1V2H2V1Z");
+    }
+
+    #[test]
+    fn bits_to_paths_ref_001() {
+        let bits = vec![vec![ 1,1,0 ],
+                        vec![ 1,0,1 ]];
+
+        assert_eq!(bits_to_paths_ref(&bits, true), bits_to_paths(bits.to_vec(), true));
+        assert_eq!(bits_to_paths_ref(&bits, false), "M0 0H2V1H1V2H0M2 1H3V2H2");
+        assert_eq!(bits_to_paths_ref(&[vec![]], true), bits_to_paths(vec![vec![]], true));
+    }
+
+    #[test]
+    fn bits_to_path_list_001() {
+        let bits = vec![vec![ 1,1,1,0,1 ],
+                        vec![ 1,0,1,0,0 ],
+                        vec![ 1,1,1,0,1 ]];
+
+        let paths = bits_to_path_list(&bits, false);
+        assert_eq!(paths, vec!["M0 0H3V3H0", "M4 0H5V1H4", "M1 1V2H2V1", "M4 2H5V3H4"]);
+        assert_eq!(paths.concat(), bits_to_paths(bits.to_vec(), false));
+        assert_eq!(bits_to_path_list(&bits, true).concat(), bits_to_paths(bits, true));
+        assert!(bits_to_path_list(&[vec![0, 0]], true).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn bits_to_paths_parallel_001() {
+        let (width, height) = (200i32, 150i32);
+        let bits: Vec<Vec<i8>> = (0..height).map(|y| (0..width).map(|x| ((x * 7 + y * 13) % 17 < 5 || (x * y) % 23 == 0) as i8).collect()).collect();
+
+        let paths = contour_tracing::array::bits_to_paths_parallel(&bits, true);
+        assert_eq!(paths, contour_tracing::array::bits_to_paths_parallel(&bits, true));
+        let mut parallel: Vec<&str> = paths.split_inclusive('Z').collect();
+        let mut sequential = bits_to_path_list(&bits, true);
+        parallel.sort_unstable();
+        sequential.sort_unstable();
+        assert_eq!(parallel, sequential);
+        // A ring touching an island by a corner is a single component
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,0 ],
+                        vec![ 0,0,0,1 ]];
+        assert_eq!(contour_tracing::array::bits_to_paths_parallel(&bits, false), "M0 0H3V3H0M1 1V2H2V1M3 3H4V4H3");
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn bits_to_paths_parallel_002() {
+        assert_eq!(contour_tracing::array::bits_to_paths_parallel(&[], true), "");
+        assert_eq!(contour_tracing::array::bits_to_paths_parallel(&[vec![], vec![]], true), "");
+    }
+
+    #[test]
+    fn bits_to_paths_with_bbox_001() {
+        let bits = vec![vec![ 0,0,0,0 ],
+                        vec![ 0,1,0,0 ],
+                        vec![ 0,0,0,1 ],
+                        vec![ 0,0,0,0 ]];
+
+        let (paths, bbox) = bits_to_paths_with_bbox(&bits, true);
+        assert_eq!(paths, bits_to_paths_ref(&bits, true));
+        assert_eq!(bbox, Some(BBox { min_x: 1, min_y: 1, max_x: 4, max_y: 3 }));
+        assert_eq!(bbox, foreground_bbox(&bits));
+        assert_eq!(bits_to_paths_with_bbox(&[vec![0, 0]], true), (String::new(), None));
+    }
+
+    #[test]
+    fn bits_to_paths_smoothed_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        assert_eq!(bits_to_paths_smoothed(&bits, true, 0, 6), bits_to_paths_ref(&bits, true));
+        assert_eq!(bits_to_paths_smoothed(&bits, false, 0, 6), bits_to_paths_ref(&bits, false));
+        // The cut wraps around the first vertex, and the hole stays counterclockwise
+        assert_eq!(bits_to_paths_smoothed(&bits, true, 1, 6), "M0.75 0L2.25 0L3 0.75L3 2.25L2.25 3L0.75 3L0 2.25L0 0.75ZM1 1.25L1 1.75L1.25 2L1.75 2L2 1.75L2 1.25L1.75 1L1.25 1Z");
+        let paths = bits_to_paths_smoothed(&bits, false, 3, 6);
+        assert_eq!(paths.matches('M').count(), 2);
+        assert_eq!(paths.matches('L').count(), 2 * (4 * 8 - 1));
+    }
+
+    #[test]
+    fn bits_to_paths_offset_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,1 ]];
+
+        assert_eq!(bits_to_paths_offset(&bits, true, 0.0, 6), bits_to_paths_ref(&bits, true));
+        assert_eq!(bits_to_paths_offset(&bits, false, 0.0, 6), bits_to_paths_ref(&bits, false));
+        // The concave corner moves inwards, the hole shrinks then collapses
+        assert_eq!(bits_to_paths_offset(&bits, true, 0.25, 6), "M-0.25 -0.25H3.25V1.75H4.25V3.25H-0.25ZM1.25 1.25V1.75H1.75V1.25Z");
+        assert_eq!(bits_to_paths_offset(&bits, true, 0.5, 6), "M-0.5 -0.5H3.5V1.5H4.5V3.5H-0.5Z");
+        // The inverted holes and the collapsed outlines are dropped
+        let ring = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+        assert_eq!(bits_to_paths_offset(&ring, true, 1.0, 6), "M-1 -1H4V4H-1Z");
+        assert_eq!(bits_to_paths_offset(&ring, true, 2.0, 6), "M-2 -2H5V5H-2Z");
+        assert_eq!(bits_to_paths_offset(&[vec![1]], true, -0.5, 6), "");
+        assert_eq!(bits_to_paths_offset(&bits, true, -0.25, 6), "M0.25 0.25H2.75V2.25H3.75V2.75H0.25ZM0.75 0.75V2.25H2.25V0.75Z");
+        // An integer offset gives the contours of the dilation while they do not overlap
+        assert_eq!(bits_to_paths_offset(&[vec![1, 0, 0, 0, 1]], true, 1.0, 6), "M-1 -1H2V2H-1ZM3 -1H6V2H3Z");
+        assert_eq!(bits_to_paths_offset(&[vec![1, 0, 0, 0, 1]], true, 1.0, 6), bits_to_fill_and_offset_paths(&[vec![1, 0, 0, 0, 1]], 1, true).iter().map(|(_, stroke)| stroke.as_str()).collect::<String>());
+    }
+
+    #[test]
+    fn bits_to_paths_transformed_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        assert_eq!(bits_to_paths_transformed(&bits, true, [1.0, 0.0, 0.0, 1.0, 0.0, 0.0], 6), bits_to_paths_ref(&bits, true));
+        assert_eq!(bits_to_paths_transformed(&bits, false, [1.0, 0.0, 0.0, 1.0, -2.0, 0.5], 6), "M-2 0.5H1V3.5H-2M-1 1.5V2.5H0V1.5");
+        // A scale keeps the edges horizontal or vertical
+        assert_eq!(bits_to_paths_transformed(&bits, true, [2.0, 0.0, 0.0, 2.0, 0.0, 0.0], 6), bits_to_paths_scaled(&bits, true, 2.0, 6));
+        // A rotation of 45 degrees
+        let (cos, sin) = (std::f32::consts::FRAC_1_SQRT_2, std::f32::consts::FRAC_1_SQRT_2);
+        assert_eq!(bits_to_paths_transformed(&bits, true, [cos, sin, -sin, cos, 0.0, 0.0], 2), "M0 0L2.12 2.12L0 4.24L-2.12 2.12ZM0 1.41L-0.71 2.12L0 2.83L0.71 2.12Z");
+    }
+
+    #[test]
+    fn bits_to_svg_document_001() {
+        let bits = vec![vec![ 0,0,0,0 ],
+                        vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,0 ]];
+
+        let options = SvgDocOptions::new().fill("#336").stroke("a\"b").fill_rule("nonzero").size("30", "100%").background("white");
+        assert_eq!(bits_to_svg_document(&bits, true, &options), concat!(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 1 3 3" width="30" height="100%">"#,
+            r##"<rect x="0" y="1" width="3" height="3" fill="white"/><path d="M0 1H3V4H0ZM1 2V3H2V2Z" fill="#336" stroke="a&quot;b" fill-rule="nonzero"/></svg>"##));
+        // Without contours, the viewBox is the whole array of bits
+        assert_eq!(bits_to_svg_document(&[vec![0, 0]], false, &SvgDocOptions::new()),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 2 1"><path d="" fill="black" fill-rule="evenodd"/></svg>"#);
+    }
+
+    #[test]
+    fn bits_to_paths_scaled_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        assert_eq!(bits_to_paths_scaled(&bits, true, 1.0, 6), bits_to_paths_ref(&bits, true));
+        assert_eq!(bits_to_paths_scaled(&bits, false, 3.0, 6), "M0 0H9V9H0M3 3V6H6V3");
+        assert_eq!(bits_to_paths_scaled(&bits, true, 0.1, 6), "M0 0H0.3V0.3H0ZM0.1 0.1V0.2H0.2V0.1Z");
+        assert_eq!(bits_to_paths_scaled(&bits, true, -1.0, 6), "M0 0H-3V-3H0ZM-1 -1V-2H-2V-1Z");
+        // The precision rounds the fractional coordinates only
+        assert_eq!(bits_to_paths_scaled(&bits, true, 1.0 / 3.0, 2), "M0 0H1V1H0ZM0.33 0.33V0.67H0.67V0.33Z");
+        assert_eq!(bits_to_paths_scaled(&bits, true, 3.0, 0), bits_to_paths_scaled(&bits, true, 3.0, 6));
+    }
+
+    #[test]
+    fn try_bits_to_paths_001() {
+        let bits = vec![vec![ 1,1,0 ],
+                        vec![ 1,0,1 ]];
+
+        assert_eq!(try_bits_to_paths(&bits, true), Ok(bits_to_paths_ref(&bits, true)));
+        assert_eq!(try_bits_to_paths(&[vec![], vec![]], true), Ok(String::new()));
+        assert_eq!(try_bits_to_paths(&[], false), Err(TraceError::Empty));
+        assert_eq!(try_bits_to_paths(&[vec![1, 0], vec![1, 0], vec![1, 0, 1]], false), Err(TraceError::RaggedRows { row: 2, expected: 2, found: 3 }));
+        assert_eq!(TraceError::RaggedRows { row: 1, expected: 3, found: 2 }.to_string(), "the row 1 of the array of bits has 2 columns instead of 3");
+    }
+
+    #[test]
+    #[should_panic(expected = "the array of bits is malformed")]
+    fn try_bits_to_paths_002() {
+        bits_to_paths(vec![vec![1, 1], vec![1]], true);
+    }
+
+    #[test]
+    fn flat_bits_to_paths_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,1 ]];
+        let flat: Vec<u8> = vec![ 255,255,255,0,
+                                  255,  0,255,0,
+                                    2,  1,  1,1 ];
+
+        assert_eq!(flat_bits_to_paths(&flat, 4, 3, true), bits_to_paths(bits, true));
+        assert_eq!(flat_bits_to_paths(&[], 0, 3, true), "");
+        assert_eq!(flat_bits_to_paths(&[], 4, 0, false), "");
+    }
+
+    #[test]
+    fn bits_to_contours_001() {
+        let bits = vec![vec![ 1,1,1,1 ],
+                        vec![ 1,0,0,1 ],
+                        vec![ 1,1,1,1 ],
+                        vec![ 0,0,0,0 ],
+                        vec![ 0,1,0,0 ]];
+
+        let contours = bits_to_contours(&bits);
+        assert_eq!(contours, vec![Contour::new(vec![(0, 0), (4, 0), (4, 3), (0, 3)], false),
+                                  Contour::new(vec![(1, 1), (1, 2), (3, 2), (3, 1)], true),
+                                  Contour::new(vec![(1, 4), (2, 4), (2, 5), (1, 5)], false)]);
+        assert!(contours.iter().all(|c| (c.signed_area() > 0) != c.is_hole));
+    }
+
+    #[test]
+    fn bits_to_hierarchy_001() {
+        // An outline with two disjoint holes, an island in the second hole, and a second outline
+        let bits = vec![vec![ 1,1,1,1,1,1,1,0,1 ],
+                        vec![ 1,0,1,0,0,0,1,0,0 ],
+                        vec![ 1,1,1,0,1,0,1,0,0 ],
+                        vec![ 0,0,1,0,0,0,1,0,0 ],
+                        vec![ 0,0,1,1,1,1,1,0,0 ]];
+
+        let island = OutlineNode { id: 2, outline: Contour::new(vec![(4, 2), (5, 2), (5, 3), (4, 3)], false), holes: vec![] };
+        let outline = Contour::new(vec![(0, 0), (7, 0), (7, 5), (2, 5), (2, 3), (0, 3)], false);
+        let holes = vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true), outlines: vec![] },
+                         HoleNode { hole: Contour::new(vec![(3, 1), (3, 4), (6, 4), (6, 1)], true), outlines: vec![island] }];
+        let square = OutlineNode { id: 1, outline: Contour::new(vec![(8, 0), (9, 0), (9, 1), (8, 1)], false), holes: vec![] };
+        assert_eq!(bits_to_hierarchy(&bits), vec![OutlineNode { id: 0, outline, holes }, square]);
+    }
+
+    #[test]
+    fn bits_to_polygon_rings_001() {
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,1,1,1,1 ]];
+
+        // The island is a separate polygon
+        let polygons = bits_to_polygon_rings(&bits);
+        assert_eq!(polygons, vec![
+            (vec![(0, 0), (5, 0), (5, 5), (0, 5), (0, 0)], vec![vec![(1, 1), (1, 4), (4, 4), (4, 1), (1, 1)]]),
+            (vec![(2, 2), (3, 2), (3, 3), (2, 3), (2, 2)], vec![]),
+        ]);
+        assert!(bits_to_polygon_rings(&[vec![0]]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "geo")]
+    fn bits_to_multipolygon_001() {
+        // An island in the hole of an outline, and another outline
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,0 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,1,1,1,1,0,0 ]];
+
+        let multipolygon = contour_tracing::array::bits_to_multipolygon(&bits);
+        let rings = bits_to_polygon_rings(&bits);
+        assert_eq!(multipolygon.0.len(), rings.len());
+        for (polygon, (exterior, interiors)) in multipolygon.0.iter().zip(&rings) {
+            let points = |ring: &geo_types::LineString<f64>| ring.0.iter().map(|c| (c.x as i32, c.y as i32)).collect::<Vec<(i32, i32)>>();
+            let area = |ring: &geo_types::LineString<f64>| ring.0.windows(2).map(|w| w[0].x * w[1].y - w[1].x * w[0].y).sum::<f64>();
+            assert_eq!(&points(polygon.exterior()), exterior);
+            assert_eq!(&polygon.interiors().iter().map(points).collect::<Vec<_>>(), interiors);
+            // Counterclockwise exteriors and clockwise interiors in the convention of the geo crate
+            assert!(area(polygon.exterior()) > 0.0);
+            assert!(polygon.interiors().iter().all(|ring| area(ring) < 0.0));
+        }
+        assert_eq!(multipolygon.0.iter().map(|p| p.interiors().len()).collect::<Vec<usize>>(), vec![1, 0, 0]);
+        assert!(contour_tracing::array::bits_to_multipolygon(&[vec![0]]).0.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "geojson")]
+    fn bits_to_geojson_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,0 ],
+                        vec![ 0,0,0,1 ]];
+
+        // A ring with its hole (8 pixels) and a pixel touching it by a corner
+        let geojson = contour_tracing::array::bits_to_geojson(&bits);
+        assert_eq!(geojson, concat!(r#"{"type":"FeatureCollection","features":["#,
+            r#"{"type":"Feature","geometry":{"type":"Polygon","coordinates":[[[0,0],[3,0],[3,3],[0,3],[0,0]],[[1,1],[1,2],[2,2],[2,1],[1,1]]]},"properties":{"is_hole":false,"area":8}},"#,
+            r#"{"type":"Feature","geometry":{"type":"Polygon","coordinates":[[[3,3],[4,3],[4,4],[3,4],[3,3]]]},"properties":{"is_hole":false,"area":1}}]}"#));
+        assert_eq!(contour_tracing::array::bits_to_geojson(&[vec![0]]), r#"{"type":"FeatureCollection","features":[]}"#);
+    }
+
+    #[test]
+    fn bits_to_contours_meta_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,1 ],
+                        vec![ 1,1,1,0 ]];
+
+        let meta = bits_to_contours_meta(&bits);
+        assert_eq!(meta, vec![
+            ContourMeta { signed_area: 10.0, is_hole: false, vertex_count: 8, bbox: BBox { min_x: 0, min_y: 0, max_x: 4, max_y: 3 }, principal_endpoints: Some(((0, 0), (4, 2))) },
+            ContourMeta { signed_area: -1.0, is_hole: true, vertex_count: 4, bbox: BBox { min_x: 1, min_y: 1, max_x: 2, max_y: 2 }, principal_endpoints: Some(((1, 1), (2, 1))) },
+        ]);
+        assert_eq!(meta.iter().map(|m| m.signed_area).sum::<f64>(), 9.0);
+        assert_eq!(meta.len(), bits_to_contours(&bits).len());
+        for (m, contour) in meta.iter().zip(bits_to_contours(&bits)) {
+            assert_eq!(m.principal_endpoints, contour.principal_endpoints());
+        }
+        assert!(bits_to_contours_meta(&[vec![0, 0]]).is_empty());
+    }
+
+    #[test]
+    fn bits_to_paths_with_001() {
+        // A cross of diagonal bits, a diamond of diagonal bits around a hole, and two holes touching by a corner
+        let cross = vec![vec![ 1,0,1 ],
+                         vec![ 0,1,0 ],
+                         vec![ 1,0,1 ]];
+        let diamond = vec![vec![ 0,1,0 ],
+                           vec![ 1,0,1 ],
+                           vec![ 0,1,0 ]];
+        let holes = vec![vec![ 1,1,1,1 ],
+                         vec![ 1,0,1,1 ],
+                         vec![ 1,1,0,1 ],
+                         vec![ 1,1,1,1 ]];
+
+        assert_eq!(bits_to_paths_with(&cross, true, Connectivity::Four), bits_to_paths(cross.to_vec(), true));
+        assert_eq!(bits_to_paths_with(&cross, true, Connectivity::Eight), "M0 0H1V1H2V0H3V1H2V2H3V3H2V2H1V3H0V2H1V1H0Z");
+        assert_eq!(bits_to_paths_with(&diamond, false, Connectivity::Eight), "M1 0H2V1H3V2H2V3H1V2H0V1H1M1 1V2H2V1");
+        assert_eq!(bits_to_paths_with(&holes, true, Connectivity::Eight), "M0 0H4V4H0ZM1 1V2H2V1ZM2 2V3H3V2Z");
+    }
+
+    #[test]
+    fn bits_to_paths_into_001() {
+        // A writer which fails after some bytes
+        struct Limited(String, usize);
+        impl std::fmt::Write for Limited {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                if self.0.len() + s.len() > self.1 { return Err(std::fmt::Error); }
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        let mut paths = String::from("M9 9");
+        bits_to_paths_into(&bits, false, &mut paths);
+        assert_eq!(paths, "M9 9".to_owned() + &bits_to_paths(bits.to_vec(), false));
+        let mut written = String::new();
+        assert_eq!(write_paths(&bits, true, &mut written), Ok(()));
+        assert_eq!(written, bits_to_paths(bits.to_vec(), true));
+        let mut limited = Limited(String::new(), 12);
+        assert_eq!(write_paths(&bits, true, &mut limited), Err(std::fmt::Error));
+        assert!(limited.0.starts_with("M0 0H3V3H0Z") && limited.0.len() < written.len());
+    }
+
+    #[test]
+    fn trace_into_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+        let mut contours = Vec::new();
+        trace_into(&bits, &mut contours);
+
+        assert_eq!(contours, vec![Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false),
+                                  Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true)]);
+    }
+
+    #[test]
+    fn trace_into_002() {
+        let bits = vec![vec![ 1,0,1,0,1 ]];
+        let mut contours = vec![Contour::new(vec![(9, 9); 16], true); 5];
+        trace_into(&bits, &mut contours);
+
+        assert_eq!(contours.len(), 3);
+        assert_eq!(contours[2], Contour::new(vec![(4, 0), (5, 0), (5, 1), (4, 1)], false));
+        assert!(contours[0].points.capacity() >= 16);
+
+        trace_into(&[vec![ 0 ]], &mut contours);
+        assert!(contours.is_empty());
+    }
+
+    #[test]
+    fn bits_to_paths_with_options_001() {
+        let bits = vec![vec![ 1,1,0,1,1,1,0,1,1 ],
+                        vec![ 1,0,0,0,0,0,0,0,1 ],
+                        vec![ 0,0,0,1,1,1,0,0,0 ],
+                        vec![ 1,0,1,0,0,0,1,0,1 ],
+                        vec![ 1,0,1,0,0,0,1,0,1 ],
+                        vec![ 1,0,1,0,0,0,1,0,1 ],
+                        vec![ 0,0,0,1,1,1,0,0,0 ],
+                        vec![ 1,0,0,0,0,0,0,0,1 ],
+                        vec![ 1,1,0,1,1,1,0,1,1 ]];
+        let options = TraceOptions::new().scan_order(ScanOrder::Morton(2));
+
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H2V1H1V2H0M3 0H6V1H3M7 0H9V2H8V1H7M3 2H6V3H3M0 3H1V6H0M2 3H3V6H2M6 3H7V6H6M8 3H9V6H8M3 6H6V7H3M0 7H1V8H2V9H0M8 7H9V9H7V8H8M3 8H6V9H3");
+    }
+
+    #[test]
+    fn bits_to_paths_with_options_002() {
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,0,1,0,1 ],
+                        vec![ 1,1,1,1,1 ],
+                        vec![ 0,1,0,1,1 ],
+                        vec![ 1,1,1,0,1 ]];
+        let options = TraceOptions::new().close_paths(true).scan_order(ScanOrder::Morton(3));
+
+        assert_eq!(bits_to_paths_with_options(&bits, &options), bits_to_paths(bits, true));
+    }
+
+    #[test]
+    fn bits_to_paths_with_options_003() {
+        // A diamond of diagonal bits joined to its center: one outline around four holes
+        let bits = vec![vec![ 0,0,1,0,0 ],
+                        vec![ 0,1,0,1,0 ],
+                        vec![ 1,0,1,0,1 ],
+                        vec![ 0,1,0,1,0 ],
+                        vec![ 0,0,1,0,0 ]];
+        let options = TraceOptions::new().close_paths(true).connectivity(Connectivity::Eight);
+
+        let paths = bits_to_paths_with_options(&bits, &options);
+        assert_eq!(paths, bits_to_paths_with(&bits, true, Connectivity::Eight));
+        assert_eq!(paths.matches('M').count(), 5);
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().scan_order(ScanOrder::Morton(2))), paths);
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().max_depth(1)), paths[..paths.find('Z').unwrap() + 1]);
+        assert_eq!(bits_to_paths_with_options(&bits, &TraceOptions::new().close_paths(true)), bits_to_paths(bits, true));
+    }
+
+    #[test]
+    fn bits_to_paths_with_options_004() {
+        // A bit between four holes touching by their corners is not outlined by the row-major scan
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,1,0,1,1 ],
+                        vec![ 1,0,1,0,1 ],
+                        vec![ 1,1,0,1,1 ],
+                        vec![ 1,1,1,1,1 ]];
+        let options = TraceOptions::new().close_paths(true);
+
+        for size in 1..6 {
+            let morton = options.clone().scan_order(ScanOrder::Morton(size));
+            assert_eq!(bits_to_paths_with_options(&bits, &morton), bits_to_paths_with_options(&bits, &options));
+            for max_depth in 1..4 {
+                assert_eq!(bits_to_paths_with_options(&bits, &morton.clone().max_depth(max_depth)), bits_to_paths_with_options(&bits, &options.clone().max_depth(max_depth)));
+            }
+        }
+        assert!(!bits_to_paths_with_options(&bits, &options.scan_order(ScanOrder::Morton(2))).contains("M2 2H3V3H2Z"));
+    }
+
+    #[test]
+    fn bits_to_contours_and_simplified_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+        let pairs = bits_to_contours_and_simplified(&bits, 0.5);
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].0, pairs[0].1);
+        assert_eq!(pairs[1].0, Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true));
+        assert_eq!(pairs[1].0, pairs[1].1);
+    }
+
+    #[test]
+    fn bits_to_paths_simplified_001() {
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+
+        assert_eq!(bits_to_paths_simplified(&bits, true, 0.0), bits_to_paths(bits.to_vec(), true));
+        assert_eq!(bits_to_paths_simplified(&bits, false, 0.0), bits_to_paths(bits.to_vec(), false));
+        assert_eq!(bits_to_paths_simplified(&bits, true, 0.5), bits_to_paths(bits, true));
+        let stairs = vec![vec![ 1,0,0,0 ],
+                          vec![ 1,1,0,0 ],
+                          vec![ 1,1,1,0 ],
+                          vec![ 1,1,1,1 ]];
+        assert_eq!(bits_to_paths_simplified(&stairs, true, 0.8), "M0 0L4 4H0Z");
+        assert_eq!(bits_to_paths_simplified(&stairs, false, 0.5), "M0 0H1V1H2V2H3L4 4H0");
+    }
+
+    #[test]
+    fn bits_to_fill_and_offset_paths_001() {
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,1,0,1,1 ]];
+        let paths = bits_to_fill_and_offset_paths(&bits, 1, true);
+
+        assert_eq!(paths, vec![("M0 0H5V5H3V4H4V1H1V4H2V5H0Z".to_string(), "M-1 -1H6V6H-1ZM2 2V3H3V2Z".to_string())]);
+    }
+
+    #[test]
+    fn bits_to_fill_and_offset_paths_002() {
+        let bits = vec![vec![ 1,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,0,1 ],
+                        vec![ 1,0,0,0,0,1 ],
+                        vec![ 1,0,0,0,0,1 ],
+                        vec![ 1,1,1,1,1,1 ]];
+        let paths = bits_to_fill_and_offset_paths(&bits, -1, false);
+
+        assert_eq!(paths, vec![("M0 0H6V5H0".to_string(), "M1 1H5V4H1".to_string()),
+                               ("M1 1V4H5V1".to_string(), "M0 0V5H6V0".to_string())]);
+    }
+
+    #[test]
+    fn trace_fn_001() {
+        assert_eq!(trace_fn(0, 0, |_, _| true, true), "");
+        assert_eq!(trace_fn(4, 2, |_, _| false, true), "");
+    }
+
+    #[test]
+    fn trace_fn_002() {
+        let bits = vec![vec![ 1,0,1,0 ],
+                        vec![ 0,1,0,1 ],
+                        vec![ 1,0,1,0 ]];
+
+        assert_eq!(trace_fn(4, 3, |x, y| (x + y) % 2 == 0, false), bits_to_paths(bits, false));
+    }
+
+    #[test]
+    fn bits_to_glyph_paths_001() {
+        // The letter "o"
+        let bits = vec![vec![ 0,1,1,0 ],
+                        vec![ 1,1,1,1 ],
+                        vec![ 1,0,0,1 ],
+                        vec![ 1,1,1,1 ],
+                        vec![ 0,1,1,0 ]];
+
+        assert_eq!(bits_to_glyph_paths(&bits, FontWinding::TrueType, true), "M1 5H3V4H4V1H3V0H1V1H0V4H1ZM1 3V2H3V3Z");
+        assert_eq!(bits_to_glyph_paths(&bits, FontWinding::PostScript, true), "M1 5V4H0V1H1V0H3V1H4V4H3V5ZM1 3H3V2H1Z");
+    }
+
+    #[test]
+    fn bits_to_paths_filtered_001() {
+        // An island in the hole of a ring
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1 ],
+                        vec![ 1,1,1,1,1 ]];
+
+        assert_eq!(bits_to_paths_filtered(&bits, ContourFilter::All, false), bits_to_paths_ref(&bits, false));
+        assert_eq!(bits_to_paths_filtered(&bits, ContourFilter::OutlinesOnly, true), "M0 0H5V5H0ZM2 2H3V3H2Z");
+        assert_eq!(bits_to_paths_filtered(&bits, ContourFilter::HolesOnly, true), "M1 1V4H4V1Z");
+        assert_eq!(bits_to_paths_filtered(&[vec![1]], ContourFilter::HolesOnly, true), "");
+    }
+
+    #[test]
+    fn bits_to_paths_for_fill_rule_001() {
+        // The sign of the area of each contour is the opposite of the sign of its parent in the hierarchy
+        fn check_outline(node: &OutlineNode, depth: usize) -> usize {
+            assert!(node.outline.signed_area() > 0, "{:?}", node.outline);
+            node.holes.iter().map(|hole| {
+                assert!(hole.hole.signed_area() < 0, "{:?}", hole.hole);
+                hole.outlines.iter().map(|island| check_outline(island, depth + 2)).max().unwrap_or(depth + 1)
+            }).max().unwrap_or(depth)
+        }
+
+        // An island in a hole in an outline, next to an outline with two holes
+        let bits = vec![vec![ 1,1,1,1,1,1,0,1,1,1,1 ],
+                        vec![ 1,0,0,0,0,1,0,1,0,1,1 ],
+                        vec![ 1,0,1,1,0,1,0,1,1,1,1 ],
+                        vec![ 1,0,1,1,0,1,0,1,1,0,1 ],
+                        vec![ 1,0,0,0,0,1,0,1,1,1,1 ],
+                        vec![ 1,1,1,1,1,1,0,0,0,0,0 ]];
+
+        for &rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+            assert_eq!(bits_to_paths_for_fill_rule(&bits, rule, true), Ok(bits_to_paths_ref(&bits, true)));
+        }
+        let hierarchy = bits_to_hierarchy(&bits);
+        assert_eq!(hierarchy.iter().map(|node| check_outline(node, 0)).collect::<Vec<usize>>(), vec![2, 1]);
+        assert_eq!(FillRule::EvenOdd.as_str(), "evenodd");
+        assert_eq!(FillRule::NonZero.as_str(), "nonzero");
+    }
+
+    #[test]
+    fn foreground_bbox_001() {
+        let bits = vec![vec![ 0,0,0,0,0,0 ],
+                        vec![ 0,0,0,1,0,0 ],
+                        vec![ 0,1,0,0,0,0 ],
+                        vec![ 0,0,0,0,0,0 ]];
+
+        let bbox = foreground_bbox(&bits);
+        assert_eq!(bbox, Some(BBox { min_x: 1, min_y: 1, max_x: 4, max_y: 3 }));
+        let mut traced: Vec<Contour> = Vec::new();
+        trace_into(&bits, &mut traced);
+        let union = traced.iter().filter_map(|c| c.bbox()).reduce(|a, b| BBox { min_x: a.min_x.min(b.min_x), min_y: a.min_y.min(b.min_y), max_x: a.max_x.max(b.max_x), max_y: a.max_y.max(b.max_y) });
+        assert_eq!(bbox, union);
+    }
+
+    #[test]
+    fn foreground_bbox_002() {
+        assert_eq!(foreground_bbox(&[vec![ 0,0,0 ], vec![ 0,0,0 ]]), None);
+        assert_eq!(foreground_bbox(&[]), None);
+    }
+
+    // Convert relative SVG Path commands (as emitted by the tracer) to absolute ones
+    fn to_absolute(relative: &str) -> String {
+        let (mut start, mut current) = ((0, 0), (0, 0));
+        let mut absolute = String::new();
+        let mut chars = relative.chars().peekable();
+        while let Some(command) = chars.next() {
+            let mut numbers: Vec<i32> = Vec::new();
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphabetic() { break; }
+                chars.next();
+                if c == ' ' { numbers.push(number.parse().unwrap()); number.clear(); } else { number.push(c); }
+            }
+            if !number.is_empty() { numbers.push(number.parse().unwrap()); }
+            match command {
+                'm' => { current = (current.0 + numbers[0], current.1 + numbers[1]); start = current; absolute.push_str(&format!("M{} {}", current.0, current.1)); }
+                'h' => { current.0 += numbers[0]; absolute.push_str(&format!("H{}", current.0)); }
+                'v' => { current.1 += numbers[0]; absolute.push_str(&format!("V{}", current.1)); }
+                _ => { current = start; absolute.push('Z'); }
+            }
+        }
+        absolute
+    }
+
+    #[test]
+    fn relative_coordinates_001() {
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+
+        let relative = bits_to_paths_with_options(&bits, &TraceOptions::new().relative_coordinates(true));
+        assert_eq!(relative, "m1 0h3v1h-3m5 -1h5v5h-5m-6 -4h1v3h-1m4 -3h1v3h-1m3 -3v3h3v-3m-2 1h1v1h-1m-7 1h3v1h-3");
+        assert_eq!(to_absolute(&relative), bits_to_paths(bits.to_vec(), false));
+        let relative = bits_to_paths_with_options(&bits, &TraceOptions::new().close_paths(true).relative_coordinates(true));
+        assert_eq!(to_absolute(&relative), bits_to_paths(bits, true));
+    }
+
+    #[test]
+    fn relative_coordinates_002() {
+        let (width, height) = (400i32, 300i32);
+        let bits: Vec<Vec<i8>> = (0..height).map(|y| (0..width).map(|x| ((x / 7 + y / 5) % 3 == 0 && (x * y) % 11 != 0) as i8).collect()).collect();
+
+        let absolute = bits_to_paths(bits.to_vec(), true);
+        let relative = bits_to_paths_with_options(&bits, &TraceOptions::new().close_paths(true).relative_coordinates(true));
+        assert_eq!(to_absolute(&relative), absolute);
+        assert!(relative.len() < absolute.len());
+    }
+
+    #[test]
+    fn first_contour_path_001() {
+        let bits = vec![vec![ 0,0,0,0,0,0 ],
+                        vec![ 0,1,1,1,0,1 ],
+                        vec![ 0,1,0,1,0,0 ],
+                        vec![ 0,1,1,1,0,0 ]];
+
+        let first = first_contour_path(&bits, false);
+        assert_eq!(first, Some("M1 1H4V4H1".to_string()));
+        assert!(bits_to_paths(bits.to_vec(), false).starts_with(first.unwrap().as_str()));
+        assert_eq!(first_contour_path(&bits, true), Some("M1 1H4V4H1Z".to_string()));
+    }
+
+    #[test]
+    fn first_contour_path_002() {
+        assert_eq!(first_contour_path(&[vec![ 0,0,0 ], vec![ 0,0,0 ]], false), None);
+    }
+
+    // Split absolute SVG Path commands into unit edges, translated by (dx, dy)
+    fn unit_edges(paths: &str, dx: i32, dy: i32) -> Vec<((i32, i32), (i32, i32))> {
+        let mut edges = Vec::new();
+        for path in paths.split('M').filter(|p| !p.is_empty()) {
+            let closed = path.ends_with('Z');
+            let path = path.trim_end_matches('Z');
+            let mut commands = path.split(['H', 'V']);
+            let start: Vec<i32> = commands.next().unwrap().split(' ').map(|n| n.parse().unwrap()).collect();
+            let mut points = vec![(start[0], start[1])];
+            let mut horizontal = path.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c == 'H');
+            for n in commands {
+                let n: i32 = n.parse().unwrap();
+                let &(x, y) = points.last().unwrap();
+                points.push(if horizontal.next().unwrap() { (n, y) } else { (x, n) });
+            }
+            if closed { points.push(points[0]); }
+            for w in points.windows(2) {
+                let step = ((w[1].0 - w[0].0).signum(), (w[1].1 - w[0].1).signum());
+                let mut p = w[0];
+                while p != w[1] {
+                    edges.push(((p.0 + dx, p.1 + dy), (p.0 + step.0 + dx, p.1 + step.1 + dy)));
+                    p = (p.0 + step.0, p.1 + step.1);
+                }
+            }
+        }
+        edges
+    }
+
+    #[test]
+    fn bits_to_paths_with_borders_001() {
+        let bits = vec![vec![ 0,0,0,0,0,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0 ],
+                        vec![ 0,1,0,0,1,0,1,0 ],
+                        vec![ 0,1,1,1,1,1,1,0 ],
+                        vec![ 0,0,1,0,0,1,0,1 ],
+                        vec![ 1,1,0,1,1,0,1,1 ]];
+        let bit = |y: i32, x: i32| if y >= 0 && x >= 0 && y < 6 && x < 8 { bits[y as usize][x as usize] } else { 0 };
+
+        // 4 tiles of 3x4 bits draw each edge of the whole array of bits once
+        let mut edges = Vec::new();
+        for (y0, x0) in [(0, 0), (0, 4), (3, 0), (3, 4)] {
+            let tile: Vec<Vec<i8>> = (y0..y0 + 3).map(|y| (x0..x0 + 4).map(|x| bit(y, x)).collect()).collect();
+            let borders = TileBorders { top: (x0 - 1..x0 + 5).map(|x| bit(y0 - 1, x)).collect(),
+                                        bottom: (x0 - 1..x0 + 5).map(|x| bit(y0 + 3, x)).collect(),
+                                        left: (y0..y0 + 3).map(|y| bit(y, x0 - 1)).collect(),
+                                        right: (y0..y0 + 3).map(|y| bit(y, x0 + 4)).collect() };
+            edges.extend(unit_edges(&bits_to_paths_with_borders(&tile, &borders, true), x0, y0));
+        }
+        let mut expected = unit_edges(&bits_to_paths(bits, true), 0, 0);
+        edges.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(edges, expected);
+    }
+
+    #[test]
+    fn bits_to_paths_with_borders_002() {
+        // Without the adjacent tiles, the tile is traced as usual
+        let bits = vec![vec![ 1,0,0,0 ],
+                        vec![ 0,1,1,1 ],
+                        vec![ 1,1,0,1 ]];
+
+        assert_eq!(bits_to_paths_with_borders(&bits, &TileBorders::default(), true), bits_to_paths(bits, true));
+    }
+
+    #[test]
+    fn output_order_001() {
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1 ],
+                        vec![ 1,1,1,1,1,0,0 ]];
+
+        let options = TraceOptions::new().close_paths(true).output_order(OutputOrder::Canonical);
+        assert_eq!(bits_to_paths(bits.to_vec(), true), "M0 0H5V5H0ZM6 0H7V1H6ZM1 1V4H4V1ZM2 2H3V3H2ZM6 2H7V4H6Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H5V5H0ZM1 1V4H4V1ZM2 2H3V3H2ZM6 0H7V1H6ZM6 2H7V4H6Z");
+        let morton = options.clone().scan_order(ScanOrder::Morton(2));
+        assert_eq!(bits_to_paths_with_options(&bits, &morton), bits_to_paths_with_options(&bits, &options));
+    }
+
+    #[test]
+    fn bytes_to_paths_001() {
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+        let bytes: Vec<Vec<u8>> = bits.iter().map(|row| row.iter().map(|&b| b as u8 * 255).collect()).collect();
+
+        assert_eq!(bytes_to_paths(bytes.to_vec(), 255, true), bits_to_paths(bits, true));
+        assert_eq!(bytes_to_paths(bytes, 0, false), "M0 0H1V1H0M4 0H6V5H4V4H5V1H4M1 1H4V4H1M7 1H10V4H7M8 2V3H9V2M0 4H1V5H0");
+    }
+
+    #[test]
+    fn normalize_start_001() {
+        let bits = vec![vec![ 0,1,1 ],
+                        vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        let options = TraceOptions::new().close_paths(true).normalize_start(true);
+        assert_eq!(bits_to_paths(bits.to_vec(), true), "M1 0H3V4H0V1H1ZM1 2V3H2V2Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 1H1V0H3V4H0ZM1 2V3H2V2Z");
+    }
+
+    #[test]
+    fn estimate_path_len_001() {
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+        let checkerboard: Vec<Vec<i8>> = (0..120).map(|y| (0..120).map(|x| ((x + y) % 2) as i8).collect()).collect();
+
+        for bits in [bits, checkerboard] {
+            let estimate = estimate_path_len(&bits);
+            let len = bits_to_paths(bits, true).len();
+            assert!(len <= estimate && estimate <= 2 * len);
+        }
+        assert_eq!(estimate_path_len(&[vec![ 0,0 ]]), 0);
+    }
+
+    #[test]
+    fn row_range_001() {
+        let bits = vec![vec![ 1,0,1,1 ],
+                        vec![ 0,0,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 0,0,0,0 ],
+                        vec![ 1,1,0,1 ]];
+
+        let options = TraceOptions::new().close_paths(true).row_range(1..3);
+        assert_eq!(bits_to_paths(bits.to_vec(), true), "M0 0H1V1H0ZM2 0H4V1H3V3H2ZM0 2H1V3H0ZM0 4H2V5H0ZM3 4H4V5H3Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M2 0H4V1H3V3H2ZM0 2H1V3H0Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().scan_order(ScanOrder::Morton(2))), bits_to_paths_with_options(&bits, &options));
+        assert_eq!(bits_to_paths_with_options(&bits, &options.row_range(3..4)), "");
+    }
+
+    #[test]
+    fn bits_to_contours_with_options_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        let open = bits_to_contours_with_options(&bits, &TraceOptions::new());
+        assert_eq!(open, vec![Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false), Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true)]);
+        let closed = bits_to_contours_with_options(&bits, &TraceOptions::new().close_paths(true));
+        assert!(closed.iter().all(|c| c.explicitly_closed));
+        assert!(closed[1].simplify(0.0).explicitly_closed);
+        assert!(closed.iter().zip(open.iter()).all(|(c, o)| c.points == o.points && c.is_hole == o.is_hole));
+    }
+
+    #[test]
+    fn bits_to_path_fragments_001() {
+        let bits = vec![vec![ 0,1,1,1,0,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1,1 ]];
+
+        let fragments: Vec<String> = bits_to_path_fragments(&bits, false).collect();
+        assert_eq!(fragments.len(), 7);
+        assert_eq!(fragments.concat(), bits_to_paths(bits.to_vec(), false));
+        assert_eq!(bits_to_path_fragments(&bits, true).collect::<String>(), bits_to_paths(bits, true));
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn bits_to_path_fragments_002() {
+        use futures_core::Stream;
+        use std::task::{Context, Poll, Wake, Waker};
+        struct Noop;
+        impl Wake for Noop {
+            fn wake(self: std::sync::Arc<Self>) {}
+        }
+        let bits = vec![vec![ 1,0,1 ],
+                        vec![ 0,0,1 ]];
+
+        let waker = Waker::from(std::sync::Arc::new(Noop));
+        let mut context = Context::from_waker(&waker);
+        let mut fragments = bits_to_path_fragments(&bits, true);
+        let mut paths: Vec<String> = Vec::new();
+        while let Poll::Ready(Some(path)) = std::pin::Pin::new(&mut fragments).poll_next(&mut context) {
+            paths.push(path);
+        }
+        assert_eq!(paths, vec!["M0 0H1V1H0Z", "M2 0H3V2H2Z"]);
+    }
+
+    #[test]
+    fn max_depth_001() {
+        let bits = vec![vec![ 1,1,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,0,0,1 ],
+                        vec![ 1,0,1,1,1,0,1 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,0,0,1 ],
+                        vec![ 1,1,1,1,1,1,1 ]];
+
+        let options = TraceOptions::new().close_paths(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H7V7H0ZM1 1V6H6V1ZM2 2H5V5H2ZM3 3V4H4V3Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().max_depth(4)), "M0 0H7V7H0ZM1 1V6H6V1ZM2 2H5V5H2ZM3 3V4H4V3Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().max_depth(2)), "M0 0H7V7H0ZM1 1V6H6V1Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().max_depth(1).scan_order(ScanOrder::Morton(2))), "M0 0H7V7H0Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.max_depth(0)), "");
+    }
+
+    #[test]
+    fn bits_to_contours_with_ids_001() {
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1 ],
+                        vec![ 1,1,1,1,1,0,0 ]];
+
+        let scan = bits_to_contours_with_ids(&bits, &TraceOptions::new());
+        assert_eq!(scan.iter().map(|(id, _)| *id).collect::<Vec<usize>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(scan.iter().map(|(_, c)| c.clone()).collect::<Vec<Contour>>(), bits_to_contours_with_options(&bits, &TraceOptions::new()));
+        let canonical = bits_to_contours_with_ids(&bits, &TraceOptions::new().output_order(OutputOrder::Canonical).normalize_start(true));
+        assert_eq!(canonical.iter().map(|(id, _)| *id).collect::<Vec<usize>>(), vec![0, 2, 3, 1, 4]);
+        let rows = bits_to_contours_with_ids(&bits, &TraceOptions::new().row_range(2..5));
+        assert_eq!(rows.iter().map(|(id, c)| (*id, c.points[0])).collect::<Vec<(usize, (i32, i32))>>(), vec![(0, (0, 0)), (1, (1, 1)), (2, (2, 2)), (3, (6, 2))]);
+    }
+
+    #[test]
+    fn bits_to_path_elements_001() {
+        let bits = vec![vec![ 1,1,1,0,1 ],
+                        vec![ 1,0,1,0,0 ],
+                        vec![ 1,1,1,0,0 ]];
+
+        let options = TraceOptions::new().close_paths(true).output_order(OutputOrder::Canonical);
+        assert_eq!(bits_to_path_elements(&bits, &options, PATH_ELEMENT_TEMPLATE), vec![
+            r#"<path d="M0 0H3V3H0Z" data-id="0"/>"#,
+            r#"<path d="M1 1V2H2V1Z" data-id="2"/>"#,
+            r#"<path d="M4 0H5V1H4Z" data-id="1"/>"#]);
+        let relative = bits_to_path_elements(&bits, &options.relative_coordinates(true), "{id}:{d}");
+        assert_eq!(relative, vec!["0:m0 0h3v3h-3z", "2:m1 1v1h1v-1z", "1:m4 0h1v1h-1z"]);
+    }
+
+    #[test]
+    fn border_is_foreground_001() {
+        let bits = vec![vec![ 1,1,1,1,1 ],
+                        vec![ 1,0,0,1,1 ],
+                        vec![ 1,1,1,1,0 ],
+                        vec![ 0,0,1,1,0 ],
+                        vec![ 1,1,1,1,1 ]];
+
+        let options = TraceOptions::new().close_paths(true).border_is_foreground(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M1 1V2H3V1ZM4 2V4H5V2ZM0 3V4H2V3Z");
+        let all = vec![vec![ 1,1 ], vec![ 1,1 ]];
+        assert_eq!(bits_to_paths_with_options(&all, &options), "");
+        let none = vec![vec![ 0,0 ], vec![ 0,0 ]];
+        assert_eq!(bits_to_paths_with_options(&none, &options), "M0 0V2H2V0Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().scan_order(ScanOrder::Morton(2))), bits_to_paths_with_options(&bits, &options));
+    }
+
+    #[test]
+    fn border_is_foreground_002() {
+        // An island in a lake which touches the sides
+        let bits = vec![vec![ 1,0,0,0 ],
+                        vec![ 0,0,1,0 ],
+                        vec![ 0,0,0,0 ]];
+
+        let options = TraceOptions::new().close_paths(true).border_is_foreground(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M1 0V1H0V3H4V0ZM2 1H3V2H2Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().max_depth(1)), "M1 0V1H0V3H4V0Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.output_order(OutputOrder::Canonical)), "M1 0V1H0V3H4V0ZM2 1H3V2H2Z");
+    }
+
+    #[test]
+    fn quantize_001() {
+        let bits = vec![vec![ 0,0,0,0,0,0,0,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,1,0 ],
+                        vec![ 0,1,0,0,0,1,1,0,0,0 ],
+                        vec![ 0,1,0,0,0,1,1,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,0,0 ]];
+
+        let options = TraceOptions::new().close_paths(true).quantize(4);
+        // The single bit and the hole collapse once snapped, so they become the cells of the coarser grid covering them
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H8V8H0ZM8 0H12V4H8ZM0 0V4H8V0Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.quantize(1)), bits_to_paths(bits.clone(), true));
+    }
+
+    #[test]
+    fn bits_to_paths_within_bounds_001() {
+        let bits = vec![vec![ 0,0,0,0,0,0,0,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,1,0 ],
+                        vec![ 0,1,0,0,0,1,1,0,0,0 ],
+                        vec![ 0,1,0,0,0,1,1,0,0,0 ],
+                        vec![ 0,1,1,1,1,1,1,0,0,0 ]];
+
+        // The vertices are within the bounds of the array of bits, but not once quantized
+        let options = TraceOptions::new().close_paths(true);
+        assert_eq!(bits_to_paths_within_bounds(&bits, &options, 10, 5, BoundsPolicy::Error), Ok(bits_to_paths(bits.clone(), true)));
+        let quantized = options.clone().quantize(4);
+        assert_eq!(bits_to_paths_within_bounds(&bits, &quantized, 10, 5, BoundsPolicy::Error), Err(OutOfBounds { id: 1, point: (12, 0) }));
+        assert_eq!(bits_to_paths_within_bounds(&bits, &quantized, 10, 5, BoundsPolicy::Clamp), Ok("M0 0H8V4H0ZM8 0H10V4H8ZM0 0V4H8V0Z".to_string()));
+        // The parts outside are clipped and the contours entirely outside are dropped
+        assert_eq!(bits_to_paths_within_bounds(&bits, &options, 4, 3, BoundsPolicy::Clamp), Ok("M1 1H4V3H1ZM2 2V3H4V2Z".to_string()));
+        assert_eq!(bits_to_paths_within_bounds(&bits, &options.relative_coordinates(true), 4, 3, BoundsPolicy::Clamp), Ok("m1 1h3v2h-3zm1 1v1h2v-1z".to_string()));
+    }
+
+    #[test]
+    fn diff_paths_001() {
+        let a = vec![vec![ 1,1,1,0 ],
+                     vec![ 1,1,1,0 ],
+                     vec![ 1,1,1,0 ]];
+        let b = vec![vec![ 1,1,1,1 ],
+                     vec![ 1,0,1,1 ],
+                     vec![ 1,1,1,0 ]];
+
+        assert_eq!(diff_paths(&a, &b, true), Ok(("M3 0H4V2H3Z".to_string(), "M1 1H2V2H1Z".to_string())));
+        assert_eq!(diff_paths(&a, &a, true), Ok((String::new(), String::new())));
+        assert_eq!(diff_paths(&a, &b[..2], true), Err(DimensionMismatch { a: (3, 4), b: (2, 4) }));
+        assert_eq!(diff_paths(&a, &[vec![ 1,1,1,1 ], vec![ 1 ], vec![ 1 ]], true), Err(DimensionMismatch { a: (3, 4), b: (3, 4) }));
+    }
+
+    #[test]
+    fn y_up_001() {
+        let bits = vec![vec![ 1,1,1 ],
+                        vec![ 1,0,1 ],
+                        vec![ 1,1,1 ]];
+
+        // A canvas padded by 2 rows at the top
+        let options = TraceOptions::new().close_paths(true).y_up(5);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 5V2H3V5ZM1 4H2V3H1Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.relative_coordinates(true)), "m0 5v-3h3v3zm1 -1h1v-1h-1z");
+        let contours = bits_to_contours_with_options(&bits, &TraceOptions::new().y_up(3));
+        assert!(contours[0].signed_area() > 0 && contours[1].signed_area() < 0);
+    }
+
+    #[test]
+    fn corner_style_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,1 ]];
+
+        let options = TraceOptions::new().close_paths(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().corner_style(CornerStyle::Sharp)), bits_to_paths(bits.to_vec(), true));
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().corner_style(CornerStyle::Round(0.0))), bits_to_paths(bits.to_vec(), true));
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().corner_style(CornerStyle::Chamfer(0.25))),
+            "M0.25 0H2.75L3 0.25V1.75L3.25 2H3.75L4 2.25V2.75L3.75 3H0.25L0 2.75V0.25L0.25 0ZM1 1.25V1.75L1.25 2H1.75L2 1.75V1.25L1.75 1H1.25L1 1.25Z");
+        // The radii are clamped to half of the edges, the concave corners turn counterclockwise
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().corner_style(CornerStyle::Round(5.0))), concat!(
+            "M1.5 0H2A1 1 0 0 1 3 1V1.5A0.5 0.5 0 0 0 3.5 2A0.5 0.5 0 0 1 4 2.5A0.5 0.5 0 0 1 3.5 3H1.5A1.5 1.5 0 0 1 0 1.5A1.5 1.5 0 0 1 1.5 0Z",
+            "M1 1.5A0.5 0.5 0 0 0 1.5 2A0.5 0.5 0 0 0 2 1.5A0.5 0.5 0 0 0 1.5 1A0.5 0.5 0 0 0 1 1.5Z"));
+        // A single pixel is a circle, and the relative coordinates stay sharp
+        assert_eq!(bits_to_paths_with_options(&[vec![1]], &TraceOptions::new().corner_style(CornerStyle::Round(1.0))), "M0.5 0A0.5 0.5 0 0 1 1 0.5A0.5 0.5 0 0 1 0.5 1A0.5 0.5 0 0 1 0 0.5A0.5 0.5 0 0 1 0.5 0");
+        assert_eq!(bits_to_paths_with_options(&[vec![1]], &TraceOptions::new().corner_style(CornerStyle::Chamfer(1.0 / 3.0)).precision(3)), "M0.333 0H0.667L1 0.333V0.667L0.667 1H0.333L0 0.667V0.333L0.333 0");
+        assert_eq!(bits_to_paths_with_options(&[vec![1]], &options.relative_coordinates(true).corner_style(CornerStyle::Round(1.0))), "m0 0h1v1h-1z");
+    }
+
+    #[test]
+    fn min_contour_area_001() {
+        let bits = vec![vec![ 1,0,1,0,0,0,0 ],
+                        vec![ 0,1,0,0,1,1,1 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 0,0,0,0,1,1,1 ]];
+
+        assert_eq!(bits_to_paths_with_options(&bits, &TraceOptions::new().min_contour_area(1)), bits_to_paths_ref(&bits, false));
+        // The single cells of the checkerboard and the hole of the ring are skipped
+        assert_eq!(bits_to_paths_with_options(&bits, &TraceOptions::new().close_paths(true).min_contour_area(2)), "M4 1H7V4H4Z");
+        assert_eq!(bits_to_contours_with_ids(&bits, &TraceOptions::new().min_contour_area(10)).len(), 0);
+    }
+
+    #[test]
+    fn bits_to_labeled_paths_001() {
+        // An island in the hole of a ring, and a ring with two holes
+        let bits = vec![vec![ 1,1,1,1,1,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,1,0,1,0,1 ],
+                        vec![ 1,0,1,0,1,0,1,1,1,1,1 ],
+                        vec![ 1,0,0,0,1,0,0,0,0,0,0 ],
+                        vec![ 1,1,1,1,1,0,0,0,0,0,0 ]];
+
+        let paths = bits_to_labeled_paths(&bits, true);
+        assert_eq!(paths, vec![(0, "M0 0H5V5H0ZM1 1V4H4V1Z".to_string()), (1, "M6 0H11V3H6ZM7 1V2H8V1ZM9 1V2H10V1Z".to_string()), (2, "M2 2H3V3H2Z".to_string())]);
+        assert_eq!(bits_to_labeled_paths(&bits, true), paths);
+        assert_eq!(paths.iter().map(|p| p.1.matches('M').count()).sum::<usize>(), bits_to_paths_ref(&bits, true).matches('M').count());
+    }
+
+    #[test]
+    fn contour_walk_001() {
+        let bits = vec![vec![ 1,1,0 ],
+                        vec![ 0,1,1 ],
+                        vec![ 1,0,0 ]];
+
+        let steps: Vec<_> = ContourWalk::new(&bits, 0, 0).unwrap().collect();
+        assert_eq!(steps, vec![(0, 0, Direction::East), (1, 0, Direction::East), (2, 1, Direction::East), (2, 1, Direction::South),
+                               (2, 1, Direction::West), (1, 1, Direction::West), (0, 0, Direction::West), (0, 0, Direction::North)]);
+        // The cell touching the shape by a corner is another shape
+        assert_eq!(ContourWalk::new(&bits, 0, 2).unwrap().count(), 4);
+        // Any cell of the boundary starts the same turn on itself
+        let from_middle: Vec<_> = ContourWalk::new(&bits, 1, 1).unwrap().collect();
+        assert_eq!(from_middle[0], (1, 1, Direction::West));
+        assert_eq!(from_middle[..2], steps[5..7]);
+        assert_eq!(from_middle.len(), steps.len());
+        // A seed in the background, inside the shape or outside the array
+        assert!(ContourWalk::new(&bits, 2, 0).is_none());
+        assert!(ContourWalk::new(&[vec![1; 3], vec![1; 3], vec![1; 3]], 1, 1).is_none());
+        assert!(ContourWalk::new(&bits, 3, 0).is_none());
+        assert!(ContourWalk::hole(&bits, 1, 1).is_none());
+    }
+
+    #[test]
+    fn contour_walk_002() {
+        let bits = vec![vec![ 1,1,1,1 ],
+                        vec![ 0,1,0,0 ],
+                        vec![ 1,1,1,1 ]];
+
+        // The walk makes one turn of the outline, as the tracer of bits_to_paths
+        let steps: Vec<_> = ContourWalk::new(&bits, 0, 0).unwrap().collect();
+        assert_eq!(steps.len(), 19);
+        assert_eq!(steps[7..10], [(1, 1, Direction::South), (2, 2, Direction::East), (3, 2, Direction::East)]);
+        assert_eq!(steps.last(), Some(&(0, 0, Direction::North)));
+        // A seed in the middle of the outline walks the same turn from the seed
+        let from_middle: Vec<_> = ContourWalk::new(&bits, 2, 2).unwrap().collect();
+        assert_eq!(from_middle[0], (2, 2, Direction::East));
+        assert_eq!(from_middle, [&steps[8..], &steps[..8]].concat());
+        // A seed below a hole walks the hole from the background cell above it, as the tracer of the holes
+        let ring = vec![vec![ 1,1,1,1 ],
+                        vec![ 1,0,0,1 ],
+                        vec![ 1,1,1,1 ]];
+        let hole: Vec<_> = ContourWalk::hole(&ring, 1, 1).unwrap().collect();
+        assert_eq!(hole.len(), 6);
+        assert!(hole.iter().all(|&(x, y, _)| ring[y][x] == 0));
+        assert_eq!(ContourWalk::new(&ring, 1, 2).unwrap().collect::<Vec<_>>(), [&hole[2..], &hole[..2]].concat());
+        assert_eq!(ContourWalk::new(&ring, 0, 0).unwrap().count(), 14);
+        // Not a hole: outside the shapes, or in the foreground
+        assert!(ContourWalk::hole(&[vec![ 1,0 ]], 1, 0).is_none());
+        assert!(ContourWalk::hole(&ring, 0, 0).is_none());
+    }
+
+    #[test]
+    fn flip_y_001() {
+        let bits = vec![vec![ 1,1,0 ],
+                        vec![ 1,0,0 ]];
+
+        let options = TraceOptions::new().close_paths(true).flip_y(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 2V0H1V1H2V2Z");
+        assert_eq!(bits_to_contours_with_options(&bits, &options)[0].signed_area(), 3);
+        // The explicit height takes precedence
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().y_up(4)), bits_to_paths_with_options(&bits, &TraceOptions::new().close_paths(true).y_up(4)));
+        // The height is rounded like the coordinates
+        assert_eq!(bits_to_paths_with_options(&bits, &options.quantize(4)), "M0 4V0H4V4Z");
+    }
+
+    #[test]
+    fn contours_equal_001() {
+        let a = vec![vec![ 1,1,1,0 ],
+                     vec![ 1,0,1,0 ],
+                     vec![ 1,1,1,0 ]];
+        let b = vec![vec![ 1,1,1 ],
+                     vec![ 1,-1,1 ],
+                     vec![ 1,1,1 ],
+                     vec![ 0,0,0 ]];
+        let mut c = a.clone();
+        c[1][1] = 1;
+
+        for (x, y, equal) in [(&a, &b, true), (&a, &c, false), (&b, &c, false)] {
+            assert_eq!(contours_equal(x, y), equal);
+            assert_eq!(contours_equal(y, x), equal);
+            let canonical = TraceOptions::new().output_order(OutputOrder::Canonical);
+            assert_eq!(bits_to_paths_with_options(x, &canonical) == bits_to_paths_with_options(y, &canonical), equal);
+        }
+    }
+
+    #[test]
+    fn frame_boundary_001() {
+        // A region which touches the four sides
+        let bits = vec![vec![ 1,1,1,1 ],
+                        vec![ 1,0,1,1 ],
+                        vec![ 1,1,1,0 ]];
+
+        let options = TraceOptions::new().close_paths(true);
+        assert_eq!(bits_to_paths_with_options(&bits, &options), "M0 0H4V2H3V3H0ZM1 1V2H2V1Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &options.clone().frame_boundary(FrameBoundary::Closed)), bits_to_paths(bits.clone(), true));
+        let open = options.frame_boundary(FrameBoundary::Open);
+        assert_eq!(bits_to_paths_with_options(&bits, &open), "M4 2H3V3M1 1V2H2V1Z");
+        assert_eq!(bits_to_paths_with_options(&bits, &open.clone().relative_coordinates(true)), "m4 2h-1v1m-2 -2v1h1v-1z");
+        assert_eq!(bits_to_paths_with_options(&bits, &open.y_up(3)), "M3 0V1H4M1 2H2V1H1Z");
+        assert_eq!(bits_to_paths_with_options(&[vec![ 1,1 ]], &TraceOptions::new().frame_boundary(FrameBoundary::Open)), "");
+    }
+
+    #[test]
+    fn some_to_paths_001() {
+        let grid = vec![vec![ Some(7), Some(7), Some(7), None    ],
+                        vec![ Some(7), Some(3), Some(7), None    ],
+                        vec![ Some(7), Some(7), Some(7), Some(3) ]];
+        let bits: Vec<Vec<i8>> = grid.iter().map(|row| row.iter().map(|v| v.is_some() as i8).collect()).collect();
+
+        assert_eq!(some_to_paths(&grid, true), bits_to_paths(bits, true));
+        let labels = some_labels_to_paths(&grid, true);
+        assert_eq!(labels.keys().cloned().collect::<Vec<i32>>(), vec![3, 7]);
+        assert_eq!(labels[&3], "M1 1H2V2H1ZM3 2H4V3H3Z");
+        assert_eq!(labels[&7], "M0 0H3V3H0ZM1 1V2H2V1Z");
+        assert!(some_labels_to_paths(&[vec![ None::<u8> ]], true).is_empty());
+    }
+
+    #[test]
+    fn bits_to_paths_with_lengths_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,1 ]];
+
+        let options = TraceOptions::new().close_paths(true);
+        assert_eq!(bits_to_paths_with_lengths(&bits, &options), vec![("M0 0H3V2H4V3H0Z".to_string(), 14), ("M1 1V2H2V1Z".to_string(), 4)]);
+        assert_eq!(bits_to_paths_with_lengths(&bits, &options.clone().quantize(2)).iter().map(|(_, l)| *l).collect::<Vec<u64>>(), vec![16, 8]);
+        let template = r#"<path d="{d}" pathLength="{length}" data-id="{id}"/>"#;
+        assert_eq!(bits_to_path_elements(&bits, &options, template)[1], r#"<path d="M1 1V2H2V1Z" pathLength="4" data-id="1"/>"#);
+    }
+
+    #[test]
+    fn bits_to_opencv_contours_001() {
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1 ],
+                        vec![ 1,1,1,1,1,0,1 ]];
+
+        let (contours, hierarchy) = bits_to_opencv_contours(&bits, true);
+        assert_eq!(contours, vec![
+            vec![(0, 0), (0, 4), (4, 4), (4, 0)],
+            vec![(6, 0)],
+            vec![(0, 1), (1, 0), (3, 0), (4, 1), (4, 3), (3, 4), (1, 4), (0, 3)],
+            vec![(2, 2)],
+            vec![(6, 2), (6, 4)]]);
+        assert_eq!(hierarchy, vec![[1, -1, 2, -1], [4, 0, -1, -1], [-1, -1, 3, 0], [-1, -1, -1, 2], [-1, 1, -1, -1]]);
+        let (all, _) = bits_to_opencv_contours(&bits, false);
+        assert_eq!(all[0].len(), 16);
+        assert_eq!(all[4], vec![(6, 2), (6, 3), (6, 4), (6, 3)]);
+    }
+
+    #[test]
+    fn start_vertex_001() {
+        // Symmetric shapes: each contour starts at its top left vertex, whatever the scan order
+        let bits = vec![vec![ 0,0,1,0,0,0,1,1,1,1 ],
+                        vec![ 0,1,1,1,0,0,1,0,0,1 ],
+                        vec![ 1,1,0,1,1,0,1,0,0,1 ],
+                        vec![ 0,1,1,1,0,0,1,1,1,1 ],
+                        vec![ 0,0,1,0,0,0,0,0,0,0 ]];
+
+        for scan_order in [ScanOrder::RowMajor, ScanOrder::Morton(2)] {
+            let contours = bits_to_contours_with_options(&bits, &TraceOptions::new().scan_order(scan_order));
+            assert_eq!(contours.iter().map(|c| c.points[0]).collect::<Vec<(i32, i32)>>(), vec![(2, 0), (6, 0), (7, 1), (2, 2)]);
+            for contour in &contours {
+                assert!(contour.points.iter().all(|p| (p.1, p.0) >= (contour.points[0].1, contour.points[0].0)));
+            }
+        }
+        let normalized = bits_to_contours_with_options(&bits, &TraceOptions::new().normalize_start(true));
+        assert_eq!(normalized.iter().map(|c| c.points[0]).collect::<Vec<(i32, i32)>>(), vec![(0, 2), (6, 0), (7, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn trace_into_arena_001() {
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1 ],
+                        vec![ 1,1,1,1,1,0,1 ]];
+
+        let mut expected: Vec<Contour> = Vec::new();
+        trace_into(&bits, &mut expected);
+        let mut arena: Vec<(i32, i32)> = vec![(9, 9); 100];
+        let contours = trace_into_arena(&bits, &mut arena);
+        assert_eq!(contours.iter().map(|c| c.to_contour()).collect::<Vec<Contour>>(), expected);
+        assert_eq!(contours.iter().map(|c| c.points.len()).sum::<usize>(), 20);
+        assert!(trace_into_arena(&[vec![ 0 ]], &mut arena).is_empty());
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn bits_to_rectangles_001() {
+        let bits = vec![vec![ 1,1,1,1,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,0 ],
+                        vec![ 1,0,1,0,1,0,1 ],
+                        vec![ 1,0,0,0,1,0,1 ],
+                        vec![ 1,1,1,1,1,0,1 ]];
+
+        let rectangles = bits_to_rectangles(&bits);
+        assert_eq!(rectangles, vec![(0, 0, 5, 1), (6, 0, 1, 1), (0, 1, 1, 3), (4, 1, 1, 3), (2, 2, 1, 1), (6, 2, 1, 3), (0, 4, 5, 1)]);
+        // The rectangles cover each foreground bit exactly once
+        let mut covered = vec![vec![ 0; 7 ]; 5];
+        for (x, y, w, h) in rectangles {
+            for row in covered.iter_mut().skip(y as usize).take(h as usize) {
+                for bit in row.iter_mut().skip(x as usize).take(w as usize) {
+                    *bit += 1;
+                }
+            }
+        }
+        assert_eq!(covered, bits);
+    }
+
+    #[test]
+    fn masked_trace_001() {
+        let bits = vec![vec![ 1,1,1,0 ],
+                        vec![ 1,0,1,0 ],
+                        vec![ 1,1,1,1 ]];
+        let roi = vec![vec![ true,true,false,true ],
+                       vec![ true,true,false,true ],
+                       vec![ true,true,true,true ]];
+
+        assert_eq!(masked_trace(&bits, &roi, true), Ok("M0 0H2V1H1V2H4V3H0Z".to_string()));
+        let everything = vec![vec![ true; 4 ]; 3];
+        assert_eq!(masked_trace(&bits, &everything, false), Ok(bits_to_paths(bits.clone(), false)));
+        assert_eq!(masked_trace(&bits, &roi[1..], true), Err(DimensionMismatch { a: (3, 4), b: (2, 4) }));
+    }
 }
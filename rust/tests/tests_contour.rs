@@ -0,0 +1,305 @@
+// Tests with the contours
+
+#[cfg(test)]
+#[cfg(any(feature = "array", feature = "image"))]
+mod contour {
+    use contour_tracing::contour::{diff_contour_sets, from_bytes, merge_contour_sets, shared_edges, to_bytes, BBox, BBoxMetrics, BoundaryEdge, Contour, ContourSetDiff, DecodeError, HoleNode, OutlineNode};
+
+    #[test]
+    fn simplify_001() {
+        let contour = Contour::new(vec![(0, 0), (1, 0), (1, 1), (0, 1)], false);
+
+        assert_eq!(contour.simplify(10.0), Contour::new(vec![(0, 0), (1, 1), (0, 1)], false));
+    }
+
+    #[test]
+    fn simplify_002() {
+        let contour = Contour::new(vec![(0, 0), (0, 3), (1, 3), (1, 2), (2, 2), (2, 1), (3, 1), (3, 0)], true);
+
+        assert_eq!(contour.simplify(0.0), contour);
+        assert_eq!(contour.simplify(0.8), Contour::new(vec![(0, 0), (0, 3), (3, 1), (3, 0)], true));
+    }
+
+    #[test]
+    fn principal_endpoints_001() {
+        let contour = Contour::new(vec![(1, 0), (2, 0), (2, 1), (3, 1), (3, 2), (4, 2), (4, 3), (2, 3), (2, 2), (1, 2)], false);
+
+        assert_eq!(contour.principal_endpoints(), Some(((1, 0), (4, 3))));
+        assert_eq!(Contour::default().principal_endpoints(), None);
+    }
+
+    #[test]
+    fn corners_001() {
+        // A plus sign and the same plus sign as a hole
+        let outline = Contour::new(vec![(1, 0), (2, 0), (2, 1), (3, 1), (3, 2), (2, 2), (2, 3), (1, 3), (1, 2), (0, 2), (0, 1), (1, 1)], false);
+        let mut hole = outline.clone();
+        hole.points[1..].reverse();
+        hole.is_hole = true;
+
+        assert_eq!((outline.convex_corners(), outline.concave_corners()), (8, 4));
+        assert_eq!((hole.convex_corners(), hole.concave_corners()), (8, 4));
+        assert_eq!((Contour::default().convex_corners(), Contour::default().concave_corners()), (0, 0));
+    }
+
+    #[test]
+    fn normalize_start_001() {
+        let mut hole = Contour::new(vec![(2, 1), (2, 3), (3, 3), (3, 2), (4, 2), (4, 1)], true);
+        hole.normalize_start();
+
+        assert_eq!(hole, Contour::new(vec![(2, 1), (2, 3), (3, 3), (3, 2), (4, 2), (4, 1)], true));
+        let mut rotated = Contour::new(vec![(4, 2), (4, 1), (2, 1), (2, 3), (3, 3), (3, 2)], true);
+        rotated.normalize_start();
+        assert_eq!(rotated, hole);
+        let mut empty = Contour::default();
+        empty.normalize_start();
+        assert_eq!(empty, Contour::default());
+    }
+
+    #[test]
+    fn offset_001() {
+        // A dumbbell: two squares joined by a 1 pixel wide bar
+        let contour = Contour::new(vec![(0, 0), (3, 0), (3, 1), (5, 1), (5, 0), (8, 0), (8, 3), (5, 3), (5, 2), (3, 2), (3, 3), (0, 3)], false);
+
+        assert_eq!(contour.offset(0), vec![contour.clone()]);
+        assert_eq!(contour.offset(-1), vec![Contour::new(vec![(1, 1), (2, 1), (2, 2), (1, 2)], false),
+                                            Contour::new(vec![(6, 1), (7, 1), (7, 2), (6, 2)], false)]);
+    }
+
+    #[test]
+    fn offset_002() {
+        // A C shape, closed by the outset
+        let contour = Contour::new(vec![(0, 0), (5, 0), (5, 1), (1, 1), (1, 4), (5, 4), (5, 5), (0, 5)], false);
+
+        assert_eq!(contour.offset(1), vec![Contour::new(vec![(-1, -1), (6, -1), (6, 2), (2, 2), (2, 3), (6, 3), (6, 6), (-1, 6)], false)]);
+        assert_eq!(contour.offset(2), vec![Contour::new(vec![(-2, -2), (7, -2), (7, 7), (-2, 7)], false)]);
+    }
+
+    #[test]
+    fn offset_003() {
+        let hole = Contour::new(vec![(1, 1), (1, 4), (4, 4), (4, 1)], true);
+
+        assert_eq!(hole.offset(1), vec![Contour::new(vec![(0, 0), (0, 5), (5, 5), (5, 0)], true)]);
+        assert_eq!(hole.offset(-2), vec![]);
+    }
+
+    #[test]
+    fn polygon_rings_001() {
+        let island = OutlineNode { id: 1, outline: Contour::new(vec![(2, 2), (3, 2), (3, 3), (2, 3)], false), holes: vec![] };
+        let node = OutlineNode {
+            id: 0,
+            outline: Contour::new(vec![(0, 0), (5, 0), (5, 5), (0, 5)], false),
+            holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 4), (4, 4), (4, 1)], true), outlines: vec![island] }],
+        };
+        let polygons = node.polygon_rings();
+
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].1, vec![vec![(1, 1), (1, 4), (4, 4), (4, 1), (1, 1)]]);
+        assert_eq!(polygons[1], (vec![(2, 2), (3, 2), (3, 3), (2, 3), (2, 2)], vec![]));
+    }
+
+    #[test]
+    #[cfg(feature = "geo")]
+    fn into_geo_001() {
+        // The signed area of a ring (shoelace formula), positive if it is counterclockwise for the geo crate
+        fn signed_area<T: geo_types::CoordNum>(ring: &geo_types::LineString<T>) -> T {
+            ring.0.windows(2).fold(T::zero(), |area, w| area + w[0].x * w[1].y - w[1].x * w[0].y)
+        }
+
+        let island = OutlineNode { id: 1, outline: Contour::new(vec![(2, 2), (3, 2), (3, 3), (2, 3)], false), holes: vec![] };
+        let node = OutlineNode {
+            id: 0,
+            outline: Contour::new(vec![(0, 0), (5, 0), (5, 5), (0, 5)], false),
+            holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 4), (4, 4), (4, 1)], true), outlines: vec![island] }],
+        };
+        let floats = geo_types::MultiPolygon::<f64>::from(&node);
+        let polygons = node.clone().into_geo();
+
+        assert_eq!(polygons, geo_types::MultiPolygon::from(node.clone()));
+        assert_eq!(polygons.0.len(), 2);
+        assert_eq!(polygons.0[0].exterior(), &geo_types::LineString::from(vec![(0, 0), (5, 0), (5, 5), (0, 5), (0, 0)]));
+        assert_eq!(polygons.0[0].interiors(), &[geo_types::LineString::from(vec![(1, 1), (1, 4), (4, 4), (4, 1), (1, 1)])]);
+        assert!(polygons.0[1].interiors().is_empty());
+        // The exteriors are counterclockwise and the interiors clockwise, as the geo crate expects
+        for polygon in &polygons.0 {
+            assert!(polygon.exterior().is_closed() && signed_area(polygon.exterior()) > 0);
+            assert!(polygon.interiors().iter().all(|ring| ring.is_closed() && signed_area(ring) < 0));
+        }
+        assert_eq!(signed_area(floats.0[0].exterior()), 2.0 * node.outline.signed_area() as f64);
+        assert_eq!(geo_types::LineString::<i32>::from(&node.outline), polygons.0[0].exterior().clone());
+    }
+
+    #[test]
+    fn shared_edges_001() {
+        // A 3x3 square (label 0) with a 1x1 hole filled by label 1, and a 1x3 bar (label 1) on its right
+        let square = vec![Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false), Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true)];
+        let labels = vec![Contour::new(vec![(1, 1), (2, 1), (2, 2), (1, 2)], false), Contour::new(vec![(3, 1), (4, 1), (4, 2), (3, 2)], false)];
+
+        let merged = shared_edges(&[square.clone(), labels.clone()], true);
+        assert_eq!(merged, vec![BoundaryEdge { start: (0, 0), end: (3, 0), right: 0, left: None },
+                                BoundaryEdge { start: (3, 0), end: (3, 1), right: 0, left: None },
+                                BoundaryEdge { start: (3, 1), end: (3, 2), right: 0, left: Some(1) },
+                                BoundaryEdge { start: (3, 2), end: (3, 3), right: 0, left: None },
+                                BoundaryEdge { start: (3, 3), end: (0, 3), right: 0, left: None },
+                                BoundaryEdge { start: (0, 3), end: (0, 0), right: 0, left: None },
+                                BoundaryEdge { start: (1, 1), end: (1, 2), right: 0, left: Some(1) },
+                                BoundaryEdge { start: (1, 2), end: (2, 2), right: 0, left: Some(1) },
+                                BoundaryEdge { start: (2, 2), end: (2, 1), right: 0, left: Some(1) },
+                                BoundaryEdge { start: (2, 1), end: (1, 1), right: 0, left: Some(1) },
+                                BoundaryEdge { start: (3, 1), end: (4, 1), right: 1, left: None },
+                                BoundaryEdge { start: (4, 1), end: (4, 2), right: 1, left: None },
+                                BoundaryEdge { start: (4, 2), end: (3, 2), right: 1, left: None }]);
+        let whole = shared_edges(&[square, labels], false);
+        assert_eq!(whole.len(), merged.len() + 5);
+        assert_eq!(whole.iter().filter(|e| e.right == 1 && e.left == Some(0)).count(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_001() {
+        let island = OutlineNode { id: 1, outline: Contour::new(vec![(2, 2), (3, 2), (3, 3), (2, 3)], false), holes: vec![] };
+        let node = OutlineNode {
+            id: 0,
+            outline: Contour::new(vec![(0, 0), (5, 0), (5, 5), (0, 5)], false),
+            holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 4), (4, 4), (4, 1)], true), outlines: vec![island] }],
+        };
+
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(serde_json::from_str::<OutlineNode>(&json).unwrap(), node);
+        let contour = Contour::new(vec![(3, 1), (3, 2), (2, 2), (2, 1)], true);
+        assert_eq!(serde_json::from_str::<Contour>(&serde_json::to_string(&contour).unwrap()).unwrap(), contour);
+        let bbox = BBox { min_x: -1, min_y: 0, max_x: 2, max_y: 3 };
+        assert_eq!(serde_json::to_string(&bbox).unwrap(), r#"{"min_x":-1,"min_y":0,"max_x":2,"max_y":3}"#);
+        let meta = contour_tracing::contour::ContourMeta { signed_area: -1.0, is_hole: true, vertex_count: 4, bbox, principal_endpoints: Some(((-1, 0), (2, 3))) };
+        assert_eq!(serde_json::from_str::<contour_tracing::contour::ContourMeta>(&serde_json::to_string(&meta).unwrap()).unwrap(), meta);
+    }
+
+    #[test]
+    #[cfg(feature = "tessellation")]
+    fn triangles_001() {
+        // A ring with an island in its hole, and a simplified (non rectilinear) outline
+        let island = OutlineNode { id: 1, outline: Contour::new(vec![(2, 2), (3, 2), (3, 3), (2, 3)], false), holes: vec![] };
+        let node = OutlineNode {
+            id: 0,
+            outline: Contour::new(vec![(0, 0), (5, 0), (5, 5), (0, 5)], false),
+            holes: vec![HoleNode { hole: Contour::new(vec![(1, 1), (1, 4), (4, 4), (4, 1)], true), outlines: vec![island] }],
+        };
+        let diamond = OutlineNode { id: 0, outline: Contour::new(vec![(2, 0), (4, 2), (2, 4), (0, 2)], false), holes: vec![] };
+
+        for (node, area) in [(node, 17.0), (diamond, 8.0)] {
+            let triangles = node.triangles();
+            let areas: Vec<f32> = triangles.iter().map(|t| ((t[1].0 - t[0].0) * (t[2].1 - t[0].1) - (t[2].0 - t[0].0) * (t[1].1 - t[0].1)) / 2.0).collect();
+            assert!(areas.iter().all(|&a| a > 0.0));
+            assert_eq!(areas.iter().sum::<f32>(), area);
+        }
+    }
+
+    #[test]
+    fn hu_moments_001() {
+        // An L shape, translated and scaled, rotated by 90 degrees, as a hole, and reflected
+        let l = Contour::new(vec![(0, 0), (1, 0), (1, 2), (2, 2), (2, 3), (0, 3)], false);
+        let moved = Contour::new(l.points.iter().map(|p| (10 + 3 * p.0, 5 + 3 * p.1)).collect(), false);
+        let rotated = Contour::new(l.points.iter().map(|p| (3 - p.1, p.0)).collect(), false);
+        let hole = Contour::new(l.points.iter().rev().cloned().collect(), true);
+        let reflected = Contour::new(l.points.iter().rev().map(|p| (-p.0, p.1)).collect(), false);
+
+        let hu = l.hu_moments();
+        assert!(hu[0] > 0.0);
+        for other in [moved.hu_moments(), rotated.hu_moments(), hole.hu_moments()] {
+            assert!(hu.iter().zip(other.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+        }
+        let reflected = reflected.hu_moments();
+        assert!(hu[..6].iter().zip(reflected[..6].iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+        assert!(hu[6].abs() > 1e-12 && (hu[6] + reflected[6]).abs() < 1e-12);
+        assert_eq!(Contour::new(vec![], false).hu_moments(), [0.0; 7]);
+    }
+
+    #[test]
+    fn solidity_001() {
+        // A square, a plus sign, a thin comb, and a square hole
+        let square = Contour::new(vec![(0, 0), (4, 0), (4, 4), (0, 4)], false);
+        let plus = Contour::new(vec![(1, 0), (2, 0), (2, 1), (3, 1), (3, 2), (2, 2), (2, 3), (1, 3), (1, 2), (0, 2), (0, 1), (1, 1)], false);
+        let comb = Contour::new(vec![(0, 0), (1, 0), (1, 4), (2, 4), (2, 0), (3, 0), (3, 4), (4, 4), (4, 0), (5, 0), (5, 5), (0, 5)], false);
+        let hole = Contour::new(vec![(1, 1), (1, 3), (3, 3), (3, 1)], true);
+
+        assert_eq!(square.convex_hull(), square.points);
+        assert_eq!(plus.convex_hull(), vec![(1, 0), (2, 0), (3, 1), (3, 2), (2, 3), (1, 3), (0, 2), (0, 1)]);
+        assert_eq!(hole.convex_hull(), vec![(1, 1), (3, 1), (3, 3), (1, 3)]);
+        assert_eq!(square.solidity(), 1.0);
+        assert_eq!(plus.solidity(), 5.0 / 7.0);
+        assert_eq!(comb.solidity(), 17.0 / 25.0);
+        assert_eq!(hole.solidity(), 1.0);
+        assert_eq!(Contour::new(vec![(0, 0), (1, 0)], false).solidity(), 0.0);
+    }
+
+    #[test]
+    fn bbox_metrics_001() {
+        // A thin horizontal line, a square hole, and a degenerate contour
+        let line = Contour::new(vec![(2, 1), (10, 1), (10, 2), (2, 2)], false);
+        let hole = Contour::new(vec![(1, 1), (1, 3), (3, 3), (3, 1)], true);
+        let flat = Contour::new(vec![(0, 0), (3, 0)], false);
+
+        let bbox = BBox { min_x: 2, min_y: 1, max_x: 10, max_y: 2 };
+        assert_eq!(line.bbox_metrics(), Some(BBoxMetrics { bbox, width: 8, height: 1, aspect_ratio: 8.0, extent: 1.0 }));
+        assert_eq!(hole.bbox_metrics().map(|m| (m.width, m.height, m.aspect_ratio, m.extent)), Some((2, 2, 1.0, 1.0)));
+        assert_eq!(flat.bbox_metrics().map(|m| (m.width, m.height, m.aspect_ratio, m.extent)), Some((3, 0, 0.0, 0.0)));
+        assert_eq!(Contour::new(vec![], false).bbox_metrics(), None);
+    }
+
+    #[test]
+    fn merge_contour_sets_001() {
+        // Two tiles sharing a square, traced each with a hole, in any order of the sets
+        let square = Contour::new(vec![(0, 0), (2, 0), (2, 2), (0, 2)], false);
+        let mut closed = Contour::new(vec![(2, 2), (0, 2), (0, 0), (2, 0)], false);
+        closed.explicitly_closed = true;
+        let hole = Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true);
+        let outline = Contour::new(vec![(1, 1), (2, 1), (2, 2), (1, 2)], false);
+        let a = vec![hole.clone(), square.clone()];
+        let b = vec![closed, outline.clone(), Contour::new(vec![(2, 1), (1, 1), (1, 2), (2, 2)], true)];
+
+        let merged = merge_contour_sets(vec![a.clone(), b.clone()]);
+        assert_eq!(merged, vec![square, outline, hole]);
+        assert_eq!(merge_contour_sets(vec![b, a]), merged);
+        assert_eq!(merge_contour_sets(vec![]), vec![]);
+    }
+
+    #[test]
+    fn to_bytes_001() {
+        let mut closed = Contour::new(vec![(-3, 1000), (70000, 1000), (70000, 1001), (-3, 1001)], false);
+        closed.explicitly_closed = true;
+        let contours = vec![
+            (0, closed),
+            (7, Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true)),
+            (300, Contour::new(vec![(i32::MIN, i32::MAX), (i32::MAX, i32::MIN), (0, 0)], false)),
+        ];
+
+        let bytes = to_bytes(&contours);
+        assert_eq!(from_bytes(&bytes), Ok(contours.clone()));
+        assert_eq!(from_bytes(&to_bytes(&[])), Ok(vec![]));
+        assert_eq!(&bytes[..4], b"CTB\x01");
+        // The unit steps of the hole take one byte each
+        assert_eq!(to_bytes(&contours[1..2]).len(), 4 + 1 + 1 + 1 + 1 + 8);
+        assert_eq!(from_bytes(&bytes[..bytes.len() - 1]), Err(DecodeError::Truncated));
+        assert_eq!(from_bytes(b"CT"), Err(DecodeError::Truncated));
+        assert_eq!(from_bytes(b"PNG\x01"), Err(DecodeError::BadMagic));
+        assert_eq!(from_bytes(b"CTB\x02\x00"), Err(DecodeError::UnsupportedVersion(2)));
+        assert_eq!(from_bytes(b"CTB\x01\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f"), Err(DecodeError::Overflow));
+    }
+
+    #[test]
+    fn diff_contour_sets_001() {
+        let square = |x: i32, y: i32, size: i32| Contour::new(vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)], false);
+        let hole = Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true);
+        let mut rotated = square(20, 0, 2);
+        rotated.points.rotate_left(2);
+        let reference = vec![square(0, 0, 4), hole.clone(), square(10, 0, 4), square(20, 0, 2), square(30, 0, 1)];
+        let current = vec![rotated, square(0, 0, 4), Contour::new(vec![(1, 1), (1, 3), (2, 3), (2, 1)], true), square(11, 0, 4), square(40, 0, 1), square(10, 0, 4)];
+
+        assert_eq!(diff_contour_sets(&reference, &current), ContourSetDiff {
+            added: vec![3, 4],
+            removed: vec![4],
+            changed: vec![(1, 2)],
+            unchanged: vec![(0, 1), (2, 5), (3, 0)],
+        });
+        assert_eq!(diff_contour_sets(&reference, &reference).unchanged.len(), 5);
+    }
+}
@@ -3,8 +3,10 @@
 #[cfg(test)]
 #[cfg(feature = "image")]
 mod image {
-    use ::image::{Luma, open};
-    use contour_tracing::image::single_l8_to_paths;
+    use ::image::codecs::png::PngDecoder;
+    use ::image::{DynamicImage, GrayImage, ImageBuffer, ImageOutputFormat, Luma, Rgb, RgbImage, Rgba, RgbaImage, open};
+    use contour_tracing::contour::{validate, BBox, Contour, FrameBoundary, OutlineNode};
+    use contour_tracing::image::{color_to_paths_tolerant, count_foreground_l8, coverage_l8_to_paths, dynamic_to_paths, labels_l8_to_hierarchy, multi_l8_to_paths, overlay_contours, rasterize_contours, rgba8_thresholds_to_paths, single_f32_to_paths, single_l16_to_paths, single_l8_range_to_paths, single_l8_roi_to_paths, single_l8_to_paths, single_l8_to_paths_and_restore, single_l8_to_paths_preserving, single_l8_to_paths_with_frame, single_rgba8_alpha_to_paths, single_rgba8_to_paths, stream_l8_to_paths, ChannelThresholds};
 
     const PATH: &str = "tests/images/";
 
@@ -73,4 +75,355 @@ mod image {
         let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
         assert_eq!(single_l8_to_paths(&mut buffer, Luma([255]), false), "M0 0H51V26H0M1 1V25H50V1M2 2H49V24H2M3 3V23H48V3M41 4H46V5H47V10H46V11H41V10H40V5H41M4 5H9V10H4M19 5H22V6H21V7H22V6H23V9H22V8H21V9H22V10H19V9H20V8H19V9H18V6H19V7H20V6H19M28 5H33V10H28M35 5H38V6H37V7H36V6H35M42 5V6H43V7H44V6H45V5M5 6V9H8V6M10 6H13V9H10M15 6H16V7H17V8H16V9H15V8H14V7H15M24 6H25V7H24M26 6H27V7H26M29 6V7H30V6M31 6V7H32V6M34 6H35V7H36V8H35V9H34M38 6H39V9H38V8H37V7H38M41 6V9H42V8H43V7H42V6M45 6V7H44V8H45V9H46V6M6 7H7V8H6M11 7V8H12V7M25 7H26V8H25M30 7V8H31V7M24 8H25V9H24M26 8H27V9H26M29 8V9H30V8M31 8V9H32V8M36 8H37V9H38V10H35V9H36M43 8V9H42V10H45V9H44V8M22 12H41V22H22M4 13H11V19H6V15H9V17H8V16H7V18H10V14H5V20H12V13H21V21H14V15H19V19H16V17H17V18H18V16H15V20H20V14H13V21H4M23 13V21H32V14H39V20H34V16H37V18H36V17H35V19H38V15H33V21H40V13H31V20H24V14H29V18H26V16H27V17H28V15H25V19H30V13");
     }
+
+    #[test]
+    fn rgba8_thresholds_to_paths_001() {
+        let buffer = open(PATH.to_owned() + "008.png").unwrap().to_rgba8();
+        let thresholds = ChannelThresholds::new().red(128, 255).green(128, 255).blue(128, 255);
+        assert_eq!(rgba8_thresholds_to_paths(&buffer, &thresholds, true), "M0 0H3V3H0ZM1 1V2H2V1Z");
+        assert_eq!(buffer, open(PATH.to_owned() + "008.png").unwrap().to_rgba8());
+    }
+
+    #[test]
+    fn rgba8_thresholds_to_paths_002() {
+        let mut buffer = open(PATH.to_owned() + "007.png").unwrap().to_rgba8();
+        buffer.put_pixel(2, 2, Rgba([255, 0, 0, 255]));
+        let thresholds = ChannelThresholds::new().red(129, 255).green(0, 63);
+        assert_eq!(rgba8_thresholds_to_paths(&buffer, &thresholds, false), "M2 2H3V3H2");
+    }
+
+    #[test]
+    fn single_f32_to_paths_001() {
+        let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma32f();
+        let expected = single_l8_to_paths(&mut open(PATH.to_owned() + "011.png").unwrap().to_luma8(), Luma([255]), true);
+        assert_eq!(single_f32_to_paths(&buffer, 1.0, true), expected);
+        for pixel in buffer.pixels_mut() {
+            if pixel[0] < 1.0 { *pixel = Luma([f32::NAN]); }
+        }
+        assert_eq!(single_f32_to_paths(&buffer, f32::NEG_INFINITY, true), expected);
+    }
+
+    #[test]
+    fn color_to_paths_tolerant_001() {
+        // Artifacts of up to 3 on each channel around the exact colors
+        let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_rgb8();
+        let expected = single_l8_to_paths(&mut open(PATH.to_owned() + "011.png").unwrap().to_luma8(), Luma([255]), true);
+        for (i, pixel) in buffer.pixels_mut().enumerate() {
+            let noise = (i % 4) as u8;
+            *pixel = if pixel[0] == 255 { Rgb([255 - noise, 255, 255 - noise]) } else { Rgb([pixel[0].saturating_add(noise), pixel[1], pixel[2]]) };
+        }
+        assert_eq!(color_to_paths_tolerant(&buffer, Rgb([255, 255, 255]), 18, true), expected);
+        assert_ne!(color_to_paths_tolerant(&buffer, Rgb([255, 255, 255]), 0, true), expected);
+    }
+
+    #[test]
+    fn labels_l8_to_hierarchy_001() {
+        let buffer = open(PATH.to_owned() + "008.png").unwrap().to_luma8();
+        let hierarchy = labels_l8_to_hierarchy(&buffer, Luma([0]));
+        assert_eq!(hierarchy.len(), 1);
+        let components = &hierarchy[&255];
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].area(), 8);
+        assert_eq!(components[0].outline.bbox(), Some(BBox { min_x: 0, min_y: 0, max_x: 3, max_y: 3 }));
+        assert_eq!(components[0].holes[0].hole, Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true));
+        assert!(components[0].holes[0].outlines.is_empty());
+    }
+
+    #[test]
+    fn labels_l8_to_hierarchy_002() {
+        let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
+        buffer.put_pixel(1, 1, Luma([7]));
+        let hierarchy = labels_l8_to_hierarchy(&buffer, Luma([0]));
+        assert_eq!(hierarchy[&7].len(), 1);
+        let components = &hierarchy[&255];
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].holes.len(), 1);
+        let inner = &components[0].holes[0].outlines;
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].outline.points[0], (2, 2));
+        assert_eq!(inner[0].holes[0].outlines.len(), 17);
+    }
+
+    #[test]
+    fn labels_l8_to_hierarchy_003() {
+        // Nested rings of two labels: 1, 2, 1 and a center of label 2
+        let buffer = GrayImage::from_fn(7, 7, |x, y| Luma([[1, 2, 1, 2][(x.min(y).min(6 - x).min(6 - y)) as usize]]));
+        let hierarchy = labels_l8_to_hierarchy(&buffer, Luma([0]));
+        let ones = &hierarchy[&1];
+        assert_eq!(ones.len(), 1);
+        assert_eq!(ones[0].holes.len(), 1);
+        assert_eq!(ones[0].holes[0].hole.points, vec![(1, 1), (1, 6), (6, 6), (6, 1)]);
+        let inner = &ones[0].holes[0].outlines;
+        assert_eq!(inner.len(), 1);
+        assert_eq!((inner[0].id, inner[0].outline.points[0], inner[0].holes.len()), (1, (2, 2), 1));
+        let twos = &hierarchy[&2];
+        assert_eq!(twos.len(), 1);
+        assert_eq!(twos[0].holes[0].outlines[0].outline.points, vec![(3, 3), (4, 3), (4, 4), (3, 4)]);
+        assert_eq!(twos[0].area(), 16);
+    }
+
+    #[test]
+    fn count_foreground_l8_001() {
+        let buffer = open(PATH.to_owned() + "001.png").unwrap().to_luma8();
+        assert_eq!(count_foreground_l8(&buffer, Luma([255])), 0);
+    }
+
+    #[test]
+    fn count_foreground_l8_002() {
+        let buffer = open(PATH.to_owned() + "008.png").unwrap().to_luma8();
+        assert_eq!(count_foreground_l8(&buffer, Luma([255])), 8);
+        assert_eq!(count_foreground_l8(&buffer, Luma([0])), 1);
+    }
+
+    #[test]
+    fn rasterize_contours_001() {
+        // An outline with a hole and an outline partially outside the image
+        let contours = vec![Contour::new(vec![(0, 0), (3, 0), (3, 3), (0, 3)], false),
+                            Contour::new(vec![(1, 1), (1, 2), (2, 2), (2, 1)], true),
+                            Contour::new(vec![(3, -2), (6, -2), (6, 1), (3, 1)], false)];
+
+        let mask = rasterize_contours(&contours, 4, 3, false);
+        assert_eq!(mask.into_raw(), vec![255, 255, 255, 255,
+                                         255,   0, 255,   0,
+                                         255, 255, 255,   0]);
+        assert_eq!(rasterize_contours(&contours, 4, 3, true), rasterize_contours(&contours, 4, 3, false));
+    }
+
+    #[test]
+    fn rasterize_contours_002() {
+        // A diamond: the antialiased coverage is its area
+        let diamond = Contour::new(vec![(4, 0), (8, 4), (4, 8), (0, 4)], false);
+
+        let mask = rasterize_contours(std::slice::from_ref(&diamond), 8, 8, true);
+        let area: i64 = mask.pixels().map(|p| p[0] as i64).sum();
+        assert!((area - diamond.signed_area() * 255).abs() <= 64); // Rounding of each pixel
+        assert_eq!(mask.get_pixel(3, 0)[0], 128);
+        assert_eq!(mask.get_pixel(0, 0)[0], 0);
+        assert_eq!(mask.get_pixel(3, 3)[0], 255);
+    }
+
+    #[test]
+    fn rasterize_contours_003() {
+        let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
+        let mut contours = Vec::new();
+        for (_, nodes) in labels_l8_to_hierarchy(&buffer, Luma([0])) {
+            let mut stack = nodes;
+            while let Some(node) = stack.pop() {
+                contours.push(node.outline);
+                for hole in node.holes {
+                    contours.push(hole.hole);
+                    stack.extend(hole.outlines);
+                }
+            }
+        }
+        for pixel in buffer.pixels_mut() {
+            *pixel = Luma([if pixel[0] == 0 { 0 } else { 255 }]);
+        }
+        assert_eq!(rasterize_contours(&contours, buffer.width(), buffer.height(), true), buffer);
+    }
+
+    #[test]
+    fn overlay_contours_001() {
+        let buffer = open(PATH.to_owned() + "008.png").unwrap().to_luma8();
+        let red = Rgba([255, 0, 0, 255]);
+        let overlay = overlay_contours(&buffer, Luma([255]), red);
+        assert_eq!(buffer, open(PATH.to_owned() + "008.png").unwrap().to_luma8());
+        // Every foreground pixel of the ring touches the background, the hole is kept
+        for (x, y, p) in overlay.enumerate_pixels() {
+            let expected = if buffer.get_pixel(x, y)[0] == 255 { red } else { Rgba([0, 0, 0, 255]) };
+            assert_eq!(*p, expected);
+        }
+    }
+
+    #[test]
+    fn overlay_contours_002() {
+        let mut buffer = ::image::GrayImage::from_pixel(5, 4, Luma([200]));
+        buffer.put_pixel(2, 1, Luma([0]));
+        let overlay = overlay_contours(&buffer, Luma([200]), Rgba([0, 0, 255, 255]));
+        let blue: Vec<(u32, u32)> = overlay.enumerate_pixels().filter(|(_, _, p)| p[2] == 255 && p[0] == 0).map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(blue, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (0, 1), (1, 1), (3, 1), (4, 1), (0, 2), (2, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3), (4, 3)]);
+        assert_eq!(overlay.get_pixel(1, 2), &Rgba([200, 200, 200, 255]));
+    }
+
+    #[test]
+    fn coverage_l8_to_paths_001() {
+        // An anti-aliased disc (16x16 supersampling), its vertices are much closer to the circle than the pixel corners
+        let (center, radius) = (10.0f64, 7.3f64);
+        let buffer = GrayImage::from_fn(20, 20, |x, y| {
+            let inside = (0..256).filter(|i| {
+                let (sx, sy) = (x as f64 + (i % 16) as f64 / 16.0 + 1.0 / 32.0, y as f64 + (i / 16) as f64 / 16.0 + 1.0 / 32.0);
+                (sx - center).powi(2) + (sy - center).powi(2) <= radius * radius
+            }).count();
+            Luma([(inside * 255 / 256) as u8])
+        });
+        let paths = coverage_l8_to_paths(&buffer, true, 6);
+        assert_eq!(paths.matches('M').count(), 1);
+        let vertices: Vec<(f64, f64)> = paths.split(['M', 'L', 'Z']).filter(|v| !v.is_empty()).map(|v| {
+            let mut xy = v.split(' ').map(|n| n.parse::<f64>().unwrap());
+            (xy.next().unwrap(), xy.next().unwrap())
+        }).collect();
+        assert!(vertices.len() > 20);
+        assert!(vertices.iter().all(|v| ((v.0 - center).hypot(v.1 - center) - radius).abs() < 0.1));
+        // Rounded coordinates
+        let rounded = coverage_l8_to_paths(&buffer, true, 2);
+        assert!(rounded.split(['M', 'L', 'Z', ' ']).all(|n| n.split('.').nth(1).map_or(true, |decimals| decimals.len() <= 2)));
+        assert_eq!(rounded.matches('L').count(), paths.matches('L').count());
+        assert!(!coverage_l8_to_paths(&buffer, true, 0).contains('.'));
+    }
+
+    #[test]
+    fn single_l8_to_paths_with_frame_001() {
+        // The same region as in the array tests, it touches the four sides
+        let mut buffer = GrayImage::from_pixel(4, 3, Luma([255]));
+        buffer.put_pixel(1, 1, Luma([0]));
+        buffer.put_pixel(3, 2, Luma([0]));
+
+        let closed = single_l8_to_paths_with_frame(&buffer, Luma([255]), FrameBoundary::Closed, true);
+        assert_eq!(closed, "M0 0H4V2H3V3H0ZM1 1V2H2V1Z");
+        assert_eq!(closed, single_l8_to_paths(&mut buffer.clone(), Luma([255]), true));
+        assert_eq!(single_l8_to_paths_with_frame(&buffer, Luma([255]), FrameBoundary::Open, true), "M4 2H3V3M1 1V2H2V1Z");
+        assert_eq!(single_l8_to_paths_with_frame(&buffer, Luma([0]), FrameBoundary::Open, false), "M1 1H2V2H1M3 3V2H4");
+    }
+
+    #[test]
+    fn validate_001() {
+        // The complex nested contours of the test 011
+        fn flatten(nodes: &[OutlineNode], out: &mut Vec<Contour>) {
+            for node in nodes {
+                out.push(node.outline.clone());
+                for hole in &node.holes {
+                    out.push(hole.hole.clone());
+                    flatten(&hole.outlines, out);
+                }
+            }
+        }
+        let buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
+        let mut contours: Vec<Contour> = Vec::new();
+        for nodes in labels_l8_to_hierarchy(&buffer, Luma([0])).values() {
+            flatten(nodes, &mut contours);
+        }
+        assert_eq!(contours.len(), 34);
+        assert_eq!(validate(&contours), Ok(()));
+        contours[20].is_hole = !contours[20].is_hole;
+        assert_eq!(validate(&contours), Err(20));
+    }
+
+    #[test]
+    fn stream_l8_to_paths_001() {
+        // The same paths as single_l8_to_paths for each test image, decoded row by row
+        for i in 1..=11 {
+            let path = format!("{}{:03}.png", PATH, i);
+            let decoder = PngDecoder::new(std::fs::File::open(&path).unwrap()).unwrap();
+            let mut buffer = open(&path).unwrap().to_luma8();
+            assert_eq!(stream_l8_to_paths(decoder, Luma([255]), true).unwrap(), single_l8_to_paths(&mut buffer, Luma([255]), true));
+        }
+        let mut png = std::io::Cursor::new(Vec::new());
+        RgbImage::new(2, 2).write_to(&mut png, ImageOutputFormat::Png).unwrap();
+        let decoder = PngDecoder::new(std::io::Cursor::new(png.into_inner())).unwrap();
+        assert!(stream_l8_to_paths(decoder, Luma([255]), true).is_err());
+    }
+
+    #[test]
+    fn single_l8_to_paths_preserving_001() {
+        for i in 1..=11 {
+            let buffer = open(format!("{}{:03}.png", PATH, i)).unwrap().to_luma8();
+            let original = buffer.clone();
+            let paths = single_l8_to_paths_preserving(&buffer, Luma([255]), true);
+            assert_eq!(buffer, original);
+            assert_eq!(paths, single_l8_to_paths(&mut buffer.clone(), Luma([255]), true));
+        }
+    }
+
+    #[test]
+    fn single_l8_to_paths_and_restore_001() {
+        let (width, height) = (97u32, 61u32);
+        let image_buffer = GrayImage::from_fn(width, height, |x, y| Luma([if (x * 7 + y * 13) % 17 < 6 || (x * y) % 5 == 0 { 200 } else { (x % 3) as u8 }]));
+        let mask = GrayImage::from_fn(width, height, |x, y| Luma([if image_buffer.get_pixel(x, y)[0] == 200 { 200 } else { 1 }]));
+
+        let mut restored = image_buffer.clone();
+        let paths = single_l8_to_paths_and_restore(&mut restored, Luma([200]), Luma([1]), true);
+        assert_eq!(paths, single_l8_to_paths_preserving(&image_buffer, Luma([200]), true));
+        assert_eq!(restored, mask);
+        // A two-valued mask is restored byte for byte, and can be traced again
+        assert_eq!(single_l8_to_paths_and_restore(&mut restored, Luma([200]), Luma([1]), true), paths);
+        assert_eq!(restored, mask);
+    }
+
+    #[test]
+    fn dynamic_to_paths_001() {
+        let image_buffer = RgbaImage::from_fn(5, 4, |x, y| if (x + y) % 3 == 0 { Rgba([255, 0, 0, 128]) } else { Rgba([0, 0, 255, 255]) });
+        let gray = DynamicImage::ImageRgba8(image_buffer.clone()).to_luma8();
+        // The luma of pure red, whatever its alpha
+        assert_eq!(gray.get_pixel(0, 0), &Luma([54]));
+
+        let paths = single_l8_to_paths(&mut gray.clone(), Luma([54]), true);
+        assert_eq!(dynamic_to_paths(&DynamicImage::ImageRgba8(image_buffer.clone()), Luma([54]), true), paths);
+        assert_eq!(paths, single_rgba8_to_paths(&image_buffer, Rgba([255, 0, 0, 128]), true));
+        // A grayscale image is traced as is
+        assert_eq!(dynamic_to_paths(&DynamicImage::ImageLuma8(gray), Luma([54]), true), paths);
+    }
+
+    #[test]
+    fn multi_l8_to_paths_001() {
+        let image_buffer = GrayImage::from_fn(37, 23, |x, y| Luma([((x / 4 + y / 3) % 4 + (x * y) % 2) as u8]));
+        let labels = [Luma([1]), Luma([2]), Luma([4]), Luma([9])];
+
+        let paths = multi_l8_to_paths(&image_buffer, &labels, false);
+        assert_eq!(paths.len(), 4);
+        for label in &labels {
+            assert_eq!(paths[&label[0]], single_l8_to_paths_preserving(&image_buffer, *label, false));
+        }
+        assert_eq!(paths[&9], "");
+    }
+
+    #[test]
+    fn single_l8_roi_to_paths_001() {
+        // A ring of 4x4 pixels at (1, 1)
+        let mut image_buffer = GrayImage::new(6, 6);
+        for (x, y) in (1..5).flat_map(|x| (1..5).map(move |y| (x, y))).filter(|&(x, y)| x == 1 || x == 4 || y == 1 || y == 4) {
+            image_buffer.put_pixel(x, y, Luma([9]));
+        }
+
+        let whole = BBox { min_x: -2, min_y: 0, max_x: 10, max_y: 6 };
+        assert_eq!(single_l8_roi_to_paths(&image_buffer, Luma([9]), true, whole), single_l8_to_paths_preserving(&image_buffer, Luma([9]), true));
+        // The left half of the ring is closed along the side of the region
+        let left = BBox { min_x: 0, min_y: 0, max_x: 3, max_y: 6 };
+        assert_eq!(single_l8_roi_to_paths(&image_buffer, Luma([9]), true, left), "M1 1H3V2H2V4H3V5H1Z");
+        let right = BBox { min_x: 3, min_y: 2, max_x: 6, max_y: 6 };
+        assert_eq!(single_l8_roi_to_paths(&image_buffer, Luma([9]), false, right), "M4 2H5V5H3V4H4");
+        let outside = BBox { min_x: 7, min_y: 0, max_x: 9, max_y: 6 };
+        assert_eq!(single_l8_roi_to_paths(&image_buffer, Luma([9]), true, outside), "");
+    }
+
+    #[test]
+    fn single_l16_to_paths_001() {
+        // The same paths as the 8-bit version for a 0 / foreground image, and labels which differ only by their high byte
+        let mut buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
+        let wide: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(buffer.width(), buffer.height(), |x, y| Luma([if buffer.get_pixel(x, y)[0] == 255 { 65535 } else { 0 }]));
+        assert_eq!(single_l16_to_paths(&wide, Luma([65535]), true), single_l8_to_paths(&mut buffer, Luma([255]), true));
+        let labels: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(2, 1, |x, _| Luma([1 + 256 * x as u16]));
+        assert_eq!(single_l16_to_paths(&labels, Luma([257]), true), "M1 0H2V1H1Z");
+    }
+
+    #[test]
+    fn single_l8_range_to_paths_001() {
+        let buffer = open(PATH.to_owned() + "011.png").unwrap().to_luma8();
+        assert_eq!(single_l8_range_to_paths(&mut buffer.clone(), 255, 255, true), single_l8_to_paths(&mut buffer.clone(), Luma([255]), true));
+        assert_eq!(single_l8_range_to_paths(&mut buffer.clone(), 0, 0, false), single_l8_to_paths(&mut buffer.clone(), Luma([0]), false));
+        assert_eq!(single_l8_range_to_paths(&mut buffer.clone(), 255, 0, true), "");
+        let gradient = GrayImage::from_fn(4, 1, |x, _| Luma([x as u8 * 80]));
+        assert_eq!(single_l8_range_to_paths(&mut gradient.clone(), 1, 160, true), "M1 0H3V1H1Z");
+    }
+
+    #[test]
+    fn single_rgba8_to_paths_001() {
+        // An opaque red square with a half transparent red pixel inside and a transparent corner pixel
+        let mut buffer = RgbaImage::from_pixel(3, 3, Rgba([255, 0, 0, 255]));
+        buffer.put_pixel(1, 1, Rgba([255, 0, 0, 128]));
+        buffer.put_pixel(2, 2, Rgba([255, 0, 0, 0]));
+
+        assert_eq!(single_rgba8_to_paths(&buffer, Rgba([255, 0, 0, 255]), true), "M0 0H3V2H2V1H1V2H2V3H0Z");
+        assert_eq!(single_rgba8_to_paths(&buffer, Rgba([255, 0, 0, 128]), true), "M1 1H2V2H1Z");
+        assert_eq!(single_rgba8_alpha_to_paths(&buffer, true), "M0 0H3V2H2V3H0Z");
+    }
 }